@@ -0,0 +1,190 @@
+use crate::ValueKind;
+use core::fmt;
+use serde::ser::Serializer;
+
+/// Generates `#[test]` what calls every [serde::Serializer](https://docs.rs/serde/latest/serde/trait.Serializer.html)
+/// method with sample values and checks which of them return `Ok`.
+///
+/// Lists contain `value_type`s (see [metavariables](index.html#special-value_type-metavariable)),
+/// `_` stands for every `value_type` what isn't listed in the other list.
+/// Serializer expression is evaluated once per method. The test is named `serializer_conformance`
+/// unless a name is given before the serializer.
+///
+/// `i128` and `u128` are sampled like the other methods. Their default implementations in serde
+/// always fail, so a serializer accepts them only through the `i128` and `u128` arms of [`impl_serialize!`].
+/// # Example
+/// ```
+/// # use impl_serialize::{impl_serialize, test_serializer};
+/// # use serde::ser;
+/// struct IntSerializer;
+///
+/// impl ser::Serializer for IntSerializer {
+///     type Ok = ();
+///     type Error = serde::de::value::Error;
+///
+///     type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+///     type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+///     type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+///     type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+///     type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+///     type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+///     type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+///
+///     impl_serialize!(Ok(()), [i8, i16, i32, i64]);
+///
+///     impl_serialize!(Err(ser::Error::custom(value_type)), [
+///         bool,
+///         bytes,
+///         i128,
+///         u8, u16, u32, u64, u128,
+///         f32, f64,
+///         char,
+///         str,
+///         none, some, unit,
+///         unit_struct, unit_variant,
+///         newtype_struct, newtype_variant,
+///         seq, map,
+///         tuple, tuple_struct, tuple_variant,
+///         struct, struct_variant
+///     ]);
+/// }
+///
+/// test_serializer!(IntSerializer, accept [i8, i16, i32, i64], reject [_]);
+///
+/// test_serializer!(int_serializer_rejects_text: IntSerializer, accept [], reject [char, str, bytes]);
+/// ```
+#[macro_export]
+macro_rules! test_serializer {
+    ($name:ident: $serializer:expr, accept [$($accept:tt),* $(,)?], reject [$($reject:tt),* $(,)?]) => {
+        #[test]
+        fn $name() {
+            $crate::check_serializer(
+                || $serializer,
+                &[$(stringify!($accept)),*],
+                &[$(stringify!($reject)),*]
+            );
+        }
+    };
+
+    ($serializer:expr, accept [$($accept:tt),* $(,)?], reject [$($reject:tt),* $(,)?]) => {
+        $crate::test_serializer!(serializer_conformance: $serializer, accept [$($accept),*], reject [$($reject),*]);
+    };
+}
+
+/// Calls every serializer method with sample values and panics if some of `accept`
+/// returned `Err` or some of `reject` returned `Ok`. Used by [`test_serializer!`].
+#[track_caller]
+pub fn check_serializer<S, F>(serializer: F, accept: &[&str], reject: &[&str])
+where
+    S: Serializer,
+    F: Fn() -> S,
+{
+    for value_type in accept.iter().chain(reject) {
+        assert!(
+            *value_type == "_" || ValueKind::from_value_type(value_type).is_some(),
+            "unknown value_type `{}`",
+            value_type
+        );
+        assert!(
+            !(accept.contains(value_type) && reject.contains(value_type)),
+            "`{}` is both accepted and rejected",
+            value_type
+        );
+    }
+
+    let results = ValueKind::ALL.map(|kind| (kind, sample(serializer(), kind)));
+
+    let mismatches = Mismatches {
+        results: &results,
+        accept,
+        reject,
+    };
+
+    if mismatches.iter().next().is_some() {
+        panic!("serializer conformance failed:{}", mismatches);
+    }
+}
+
+/// Calls the method of `kind` with a sample value.
+fn sample<S: Serializer>(serializer: S, kind: ValueKind) -> Result<(), S::Error> {
+    match kind {
+        ValueKind::Bool => serializer.serialize_bool(true).map(drop),
+        ValueKind::I8 => serializer.serialize_i8(-8).map(drop),
+        ValueKind::I16 => serializer.serialize_i16(-16).map(drop),
+        ValueKind::I32 => serializer.serialize_i32(-32).map(drop),
+        ValueKind::I64 => serializer.serialize_i64(-64).map(drop),
+        ValueKind::I128 => serializer.serialize_i128(-128).map(drop),
+        ValueKind::U8 => serializer.serialize_u8(8).map(drop),
+        ValueKind::U16 => serializer.serialize_u16(16).map(drop),
+        ValueKind::U32 => serializer.serialize_u32(32).map(drop),
+        ValueKind::U64 => serializer.serialize_u64(64).map(drop),
+        ValueKind::U128 => serializer.serialize_u128(128).map(drop),
+        ValueKind::F32 => serializer.serialize_f32(0.5).map(drop),
+        ValueKind::F64 => serializer.serialize_f64(0.25).map(drop),
+        ValueKind::Char => serializer.serialize_char('c').map(drop),
+        ValueKind::Str => serializer.serialize_str("str").map(drop),
+        ValueKind::Bytes => serializer.serialize_bytes(b"bytes").map(drop),
+        ValueKind::None => serializer.serialize_none().map(drop),
+        ValueKind::Some => serializer.serialize_some(&()).map(drop),
+        ValueKind::Unit => serializer.serialize_unit().map(drop),
+        ValueKind::UnitStruct => serializer.serialize_unit_struct("Unit").map(drop),
+        ValueKind::UnitVariant => serializer.serialize_unit_variant("Enum", 0, "Unit").map(drop),
+        ValueKind::NewtypeStruct => serializer.serialize_newtype_struct("Newtype", &()).map(drop),
+        ValueKind::NewtypeVariant => serializer
+            .serialize_newtype_variant("Enum", 1, "Newtype", &())
+            .map(drop),
+        ValueKind::Seq => serializer.serialize_seq(Some(0)).map(drop),
+        ValueKind::Tuple => serializer.serialize_tuple(0).map(drop),
+        ValueKind::TupleStruct => serializer.serialize_tuple_struct("Tuple", 0).map(drop),
+        ValueKind::TupleVariant => serializer.serialize_tuple_variant("Enum", 2, "Tuple", 0).map(drop),
+        ValueKind::Map => serializer.serialize_map(Some(0)).map(drop),
+        ValueKind::Struct => serializer.serialize_struct("Struct", 0).map(drop),
+        ValueKind::StructVariant => serializer
+            .serialize_struct_variant("Enum", 3, "Struct", 0)
+            .map(drop),
+    }
+}
+
+struct Mismatches<'a, E> {
+    results: &'a [(ValueKind, Result<(), E>)],
+    accept: &'a [&'a str],
+    reject: &'a [&'a str],
+}
+
+impl<'a, E> Mismatches<'a, E> {
+    fn expected(&self, value_type: &str) -> Option<bool> {
+        if self.accept.contains(&value_type) {
+            Some(true)
+        } else if self.reject.contains(&value_type) {
+            Some(false)
+        } else if self.accept.contains(&"_") {
+            Some(true)
+        } else if self.reject.contains(&"_") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&'static str, &'a Result<(), E>)> + '_ {
+        self.results
+            .iter()
+            .map(|(kind, result)| (kind.as_str(), result))
+            .filter(move |(value_type, result)| {
+                matches!(self.expected(value_type), Some(ok) if ok != result.is_ok())
+            })
+    }
+}
+
+impl<'a, E: fmt::Display> fmt::Display for Mismatches<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (value_type, result) in self.iter() {
+            match result {
+                Ok(()) => write!(f, "\n    {}: expected Err, found Ok", value_type)?,
+                Err(error) => write!(f, "\n    {}: expected Ok, found Err({})", value_type, error)?,
+            }
+        }
+
+        Ok(())
+    }
+}
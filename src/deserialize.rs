@@ -0,0 +1,325 @@
+/// Macro for fast and easy implementing deserialize methods in
+/// [serde::Deserializer](https://docs.rs/serde/latest/serde/trait.Deserializer.html) trait.
+///
+/// The `impl` block must name its lifetime `'de`, because generated methods are bounded by
+/// `serde::de::Visitor<'de>`.
+/// # Example
+#[doc = include_str!("../docs/deserialize.md")]
+#[macro_export]
+macro_rules! impl_deserialize {
+    ($get_result:expr, [$($type:ident),+]) => {
+        $(
+            $crate::impl_deserialize!($get_result, $type);
+        )+
+    };
+
+    ($get_result:expr, any) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "any";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, bool) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_bool<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "bool";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, i8) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_i8<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "i8";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, i16) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_i16<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "i16";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, i32) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_i32<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "i32";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, i64) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_i64<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "i64";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, i128) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_i128<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "i128";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, u8) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_u8<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "u8";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, u16) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_u16<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "u16";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, u32) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_u32<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "u32";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, u64) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_u64<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "u64";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, u128) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_u128<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "u128";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, f32) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_f32<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "f32";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, f64) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_f64<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "f64";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, char) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_char<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "char";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, str) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_str<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "str";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, string) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_string<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "string";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, bytes) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_bytes<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "bytes";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, byte_buf) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_byte_buf<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "byte_buf";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, option) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_option<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "option";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, unit) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_unit<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "unit";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, unit_struct) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_unit_struct<V: serde::de::Visitor<'de>>(self, name: &'static str, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "unit_struct";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, newtype_struct) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(self, name: &'static str, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "newtype_struct";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, seq) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_seq<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "seq";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, tuple) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_tuple<V: serde::de::Visitor<'de>>(self, len: usize, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "tuple";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, tuple_struct) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_tuple_struct<V: serde::de::Visitor<'de>>(self, name: &'static str, len: usize, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "tuple_struct";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, map) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_map<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "map";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, struct) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_struct<V: serde::de::Visitor<'de>>(self, name: &'static str, fields: &'static [&'static str], visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "struct";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, enum) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_enum<V: serde::de::Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "enum";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, identifier) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_identifier<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "identifier";
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, ignored_any) => {
+        impl_serialize::unhygienic! {
+            fn deserialize_ignored_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> core::result::Result<V::Value, Self::Error> {
+                let value_type = "ignored_any";
+
+                $get_result
+            }
+        }
+    };
+}
@@ -0,0 +1,181 @@
+use crate::{to_value, Value, ValueError};
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Write};
+use serde::ser::Serialize;
+
+/// Serializes both values into [Value] and returns their differences, in order of `left`.
+///
+/// Fields of structs and struct variants are matched by name, entries of maps by key,
+/// elements of sequences and tuples by index. `Some`, newtypes and equal enum variants are
+/// compared by their contents at the same path. Length hints of compound values are ignored,
+/// floats are compared with `total_cmp`, so `NaN` equals itself and `0.0` differs from `-0.0`.
+/// # Example
+#[doc = include_str!("../docs/diff.md")]
+pub fn diff<L: ?Sized + Serialize, R: ?Sized + Serialize>(left: &L, right: &R) -> Result<Vec<Difference>, ValueError> {
+    let (left, right) = (to_value(left)?, to_value(right)?);
+    let mut differences = Vec::new();
+
+    compare(&mut String::new(), &left, &right, &mut differences);
+    Ok(differences)
+}
+
+/// Difference between two values at a path like `.items[3].name` or `["key"]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Difference {
+    /// Path from the root, empty for the root itself.
+    pub path: String,
+    /// What differs.
+    pub change: Change,
+}
+
+/// Kind of [Difference].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    /// Values of the same `value_type` differ.
+    Value { left: Value, right: Value },
+    /// Values have different `value_type`s.
+    Type { left: Value, right: Value },
+    /// Field, entry or element exists only in the left value.
+    Missing { left: Value },
+    /// Field, entry or element exists only in the right value.
+    Added { right: Value },
+}
+
+/// `.items[3].name: "a" != "b"`, `.port: u16 != str`, `.name: missing on the right ("a")`.
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let path = if self.path.is_empty() { "." } else { &self.path };
+
+        match &self.change {
+            Change::Value { left, right } => write!(f, "{}: {} != {}", path, left, right),
+            Change::Type { left, right } => write!(f, "{}: {} != {}", path, left.kind(), right.kind()),
+            Change::Missing { left } => write!(f, "{}: missing on the right ({})", path, left),
+            Change::Added { right } => write!(f, "{}: missing on the left ({})", path, right),
+        }
+    }
+}
+
+fn compare(path: &mut String, left: &Value, right: &Value, differences: &mut Vec<Difference>) {
+    match (left, right) {
+        (Value::Some(left), Value::Some(right)) => compare(path, left, right, differences),
+        (
+            Value::NewtypeStruct { name, value: left },
+            Value::NewtypeStruct { name: right_name, value: right },
+        ) if name == right_name => compare(path, left, right, differences),
+        (
+            Value::NewtypeVariant { name, variant, value: left, .. },
+            Value::NewtypeVariant { name: right_name, variant: right_variant, value: right, .. },
+        ) if (name, variant) == (right_name, right_variant) => compare(path, left, right, differences),
+        (Value::Seq { elements: left, .. }, Value::Seq { elements: right, .. })
+        | (Value::Tuple { elements: left, .. }, Value::Tuple { elements: right, .. }) => compare_elements(path, left, right, differences),
+        (
+            Value::TupleStruct { name, fields: left, .. },
+            Value::TupleStruct { name: right_name, fields: right, .. },
+        ) if name == right_name => compare_elements(path, left, right, differences),
+        (
+            Value::TupleVariant { name, variant, fields: left, .. },
+            Value::TupleVariant { name: right_name, variant: right_variant, fields: right, .. },
+        ) if (name, variant) == (right_name, right_variant) => compare_elements(path, left, right, differences),
+        (Value::Map { entries: left, .. }, Value::Map { entries: right, .. }) => {
+            compare_entries(path, left, right, differences)
+        }
+        (Value::Struct { name, fields: left, .. }, Value::Struct { name: right_name, fields: right, .. })
+            if name == right_name =>
+        {
+            compare_fields(path, left, right, differences)
+        }
+        (
+            Value::StructVariant { name, variant, fields: left, .. },
+            Value::StructVariant { name: right_name, variant: right_variant, fields: right, .. },
+        ) if (name, variant) == (right_name, right_variant) => compare_fields(path, left, right, differences),
+        //scalars, different kinds, names or variants
+        _ if same(left, right) => {}
+        _ => {
+            let (left, right) = (left.clone(), right.clone());
+            let change = if left.kind() == right.kind() {
+                Change::Value { left, right }
+            } else {
+                Change::Type { left, right }
+            };
+
+            differences.push(Difference {
+                path: path.clone(),
+                change,
+            });
+        }
+    }
+}
+
+fn same(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::F32(left), Value::F32(right)) => left.total_cmp(right).is_eq(),
+        (Value::F64(left), Value::F64(right)) => left.total_cmp(right).is_eq(),
+        _ => left == right,
+    }
+}
+
+fn compare_elements(path: &mut String, left: &[Value], right: &[Value], differences: &mut Vec<Difference>) {
+    for i in 0..left.len().max(right.len()) {
+        nested(path, format_args!("[{}]", i), left.get(i), right.get(i), differences);
+    }
+}
+
+fn compare_entries(path: &mut String, left: &[(Value, Value)], right: &[(Value, Value)], differences: &mut Vec<Difference>) {
+    for (key, value) in left {
+        nested(path, format_args!("[{}]", key), Some(value), find(right, key), differences);
+    }
+
+    for (key, value) in right {
+        if find(left, key).is_none() {
+            nested(path, format_args!("[{}]", key), None, Some(value), differences);
+        }
+    }
+}
+
+fn compare_fields(
+    path: &mut String,
+    left: &[(&'static str, Value)],
+    right: &[(&'static str, Value)],
+    differences: &mut Vec<Difference>,
+) {
+    for (key, value) in left {
+        nested(path, format_args!(".{}", key), Some(value), find(right, key), differences);
+    }
+
+    for (key, value) in right {
+        if find(left, key).is_none() {
+            nested(path, format_args!(".{}", key), None, Some(value), differences);
+        }
+    }
+}
+
+fn find<'a, K: PartialEq>(pairs: &'a [(K, Value)], key: &K) -> Option<&'a Value> {
+    pairs.iter().find(|(pair_key, _)| pair_key == key).map(|(_, value)| value)
+}
+
+fn nested(
+    path: &mut String,
+    segment: fmt::Arguments,
+    left: Option<&Value>,
+    right: Option<&Value>,
+    differences: &mut Vec<Difference>,
+) {
+    let len = path.len();
+    //writing into String doesn't fail
+    let _ = path.write_fmt(segment);
+
+    match (left, right) {
+        (Some(left), Some(right)) => compare(path, left, right, differences),
+        (Some(left), None) => differences.push(Difference {
+            path: path.clone(),
+            change: Change::Missing { left: left.clone() },
+        }),
+        (None, Some(right)) => differences.push(Difference {
+            path: path.clone(),
+            change: Change::Added { right: right.clone() },
+        }),
+        (None, None) => {}
+    }
+
+    path.truncate(len);
+}
@@ -0,0 +1,432 @@
+use crate as impl_serialize;
+use crate::{Erased, ValueRef};
+use core::{fmt, mem, str};
+use serde::ser::{self, Serialize, Serializer};
+
+/// Serializer of any type, what [Erasing] passes every call to.
+pub(crate) trait Sink {
+    fn call(&mut self, value: ValueRef<'_>) -> Result<(), ErasedError>;
+    fn collect_str(&mut self, value: &dyn fmt::Display) -> Result<(), ErasedError>;
+    fn element(&mut self, value: Erased<'_>) -> Result<(), ErasedError>;
+    fn field(&mut self, key: &'static str, value: Erased<'_>) -> Result<(), ErasedError>;
+    fn skip_field(&mut self, key: &'static str) -> Result<(), ErasedError>;
+    fn key(&mut self, key: Erased<'_>) -> Result<(), ErasedError>;
+    fn value(&mut self, value: Erased<'_>) -> Result<(), ErasedError>;
+    fn end(&mut self) -> Result<(), ErasedError>;
+    fn is_human_readable(&self) -> bool;
+}
+
+/// State of the real serializer, lives on the stack of `Erased::serialize`.
+pub(crate) enum Slot<S: Serializer> {
+    Serializer(S),
+    Seq(S::SerializeSeq),
+    Tuple(S::SerializeTuple),
+    TupleStruct(S::SerializeTupleStruct),
+    TupleVariant(S::SerializeTupleVariant),
+    Map(S::SerializeMap),
+    Struct(S::SerializeStruct),
+    StructVariant(S::SerializeStructVariant),
+    Ok(S::Ok),
+    Err(S::Error),
+    Empty,
+}
+
+impl<S: Serializer> Slot<S> {
+    /// Result of the erased value what was serialized into the slot.
+    pub(crate) fn finish(self, result: Result<(), ErasedError>) -> Result<S::Ok, S::Error> {
+        match (self, result) {
+            (Slot::Err(error), _) => Err(error),
+            (_, Err(error)) => Err(ser::Error::custom(error)),
+            (Slot::Ok(ok), Ok(())) => Ok(ok),
+            (_, Ok(())) => Err(ser::Error::custom("erased value was not serialized to the end")),
+        }
+    }
+
+    fn store<T>(&mut self, result: Result<T, S::Error>, state: impl FnOnce(T) -> Self) -> Result<(), ErasedError> {
+        match result {
+            Ok(value) => {
+                *self = state(value);
+                Ok(())
+            }
+            Err(error) => {
+                *self = Slot::Err(error);
+                Err(ErasedError::Stored)
+            }
+        }
+    }
+
+    fn check(&mut self, result: Result<(), S::Error>) -> Result<(), ErasedError> {
+        result.map_err(|error| {
+            *self = Slot::Err(error);
+            ErasedError::Stored
+        })
+    }
+
+    /// Stores a custom error for a call the slot is not ready for, keeping an error stored before.
+    fn invalid(&mut self, message: &'static str) -> ErasedError {
+        if !matches!(self, Slot::Err(_)) {
+            *self = Slot::Err(ser::Error::custom(message));
+        }
+
+        ErasedError::Stored
+    }
+
+    fn serializer(&mut self) -> Result<S, ErasedError> {
+        match mem::replace(self, Slot::Empty) {
+            Slot::Serializer(serializer) => Ok(serializer),
+            state => {
+                *self = state;
+                Err(self.invalid("serializer is already used"))
+            }
+        }
+    }
+}
+
+impl<S: Serializer> Sink for Slot<S> {
+    fn call(&mut self, value: ValueRef<'_>) -> Result<(), ErasedError> {
+        let serializer = self.serializer()?;
+
+        match value {
+            ValueRef::Bool(v) => self.store(serializer.serialize_bool(v), Slot::Ok),
+            ValueRef::I8(v) => self.store(serializer.serialize_i8(v), Slot::Ok),
+            ValueRef::I16(v) => self.store(serializer.serialize_i16(v), Slot::Ok),
+            ValueRef::I32(v) => self.store(serializer.serialize_i32(v), Slot::Ok),
+            ValueRef::I64(v) => self.store(serializer.serialize_i64(v), Slot::Ok),
+            ValueRef::I128(v) => self.store(serializer.serialize_i128(v), Slot::Ok),
+            ValueRef::U8(v) => self.store(serializer.serialize_u8(v), Slot::Ok),
+            ValueRef::U16(v) => self.store(serializer.serialize_u16(v), Slot::Ok),
+            ValueRef::U32(v) => self.store(serializer.serialize_u32(v), Slot::Ok),
+            ValueRef::U64(v) => self.store(serializer.serialize_u64(v), Slot::Ok),
+            ValueRef::U128(v) => self.store(serializer.serialize_u128(v), Slot::Ok),
+            ValueRef::F32(v) => self.store(serializer.serialize_f32(v), Slot::Ok),
+            ValueRef::F64(v) => self.store(serializer.serialize_f64(v), Slot::Ok),
+            ValueRef::Char(v) => self.store(serializer.serialize_char(v), Slot::Ok),
+            ValueRef::Str(v) => self.store(serializer.serialize_str(v), Slot::Ok),
+            ValueRef::Bytes(v) => self.store(serializer.serialize_bytes(v), Slot::Ok),
+            ValueRef::None => self.store(serializer.serialize_none(), Slot::Ok),
+            ValueRef::Some(value) => self.store(serializer.serialize_some(&value), Slot::Ok),
+            ValueRef::Unit => self.store(serializer.serialize_unit(), Slot::Ok),
+            ValueRef::UnitStruct { name } => self.store(serializer.serialize_unit_struct(name), Slot::Ok),
+            ValueRef::UnitVariant { name, variant_index, variant } => {
+                self.store(serializer.serialize_unit_variant(name, variant_index, variant), Slot::Ok)
+            }
+            ValueRef::NewtypeStruct { name, value } => {
+                self.store(serializer.serialize_newtype_struct(name, &value), Slot::Ok)
+            }
+            ValueRef::NewtypeVariant { name, variant_index, variant, value } => {
+                self.store(serializer.serialize_newtype_variant(name, variant_index, variant, &value), Slot::Ok)
+            }
+            ValueRef::Seq { len } => self.store(serializer.serialize_seq(len), Slot::Seq),
+            ValueRef::Tuple { len } => self.store(serializer.serialize_tuple(len), Slot::Tuple),
+            ValueRef::TupleStruct { name, len } => {
+                self.store(serializer.serialize_tuple_struct(name, len), Slot::TupleStruct)
+            }
+            ValueRef::TupleVariant { name, variant_index, variant, len } => self.store(
+                serializer.serialize_tuple_variant(name, variant_index, variant, len),
+                Slot::TupleVariant,
+            ),
+            ValueRef::Map { len } => self.store(serializer.serialize_map(len), Slot::Map),
+            ValueRef::Struct { name, len } => self.store(serializer.serialize_struct(name, len), Slot::Struct),
+            ValueRef::StructVariant { name, variant_index, variant, len } => self.store(
+                serializer.serialize_struct_variant(name, variant_index, variant, len),
+                Slot::StructVariant,
+            ),
+        }
+    }
+
+    fn collect_str(&mut self, value: &dyn fmt::Display) -> Result<(), ErasedError> {
+        let serializer = self.serializer()?;
+
+        self.store(serializer.collect_str(value), Slot::Ok)
+    }
+
+    fn element(&mut self, value: Erased<'_>) -> Result<(), ErasedError> {
+        let result = match self {
+            Slot::Seq(seq) => ser::SerializeSeq::serialize_element(seq, &value),
+            Slot::Tuple(tuple) => ser::SerializeTuple::serialize_element(tuple, &value),
+            Slot::TupleStruct(tuple_struct) => ser::SerializeTupleStruct::serialize_field(tuple_struct, &value),
+            Slot::TupleVariant(tuple_variant) => ser::SerializeTupleVariant::serialize_field(tuple_variant, &value),
+            _ => return Err(self.invalid("element outside of sequence or tuple")),
+        };
+
+        self.check(result)
+    }
+
+    fn field(&mut self, key: &'static str, value: Erased<'_>) -> Result<(), ErasedError> {
+        let result = match self {
+            Slot::Struct(r#struct) => ser::SerializeStruct::serialize_field(r#struct, key, &value),
+            Slot::StructVariant(struct_variant) => {
+                ser::SerializeStructVariant::serialize_field(struct_variant, key, &value)
+            }
+            _ => return Err(self.invalid("field outside of struct")),
+        };
+
+        self.check(result)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), ErasedError> {
+        let result = match self {
+            Slot::Struct(r#struct) => ser::SerializeStruct::skip_field(r#struct, key),
+            Slot::StructVariant(struct_variant) => ser::SerializeStructVariant::skip_field(struct_variant, key),
+            _ => return Err(self.invalid("field outside of struct")),
+        };
+
+        self.check(result)
+    }
+
+    fn key(&mut self, key: Erased<'_>) -> Result<(), ErasedError> {
+        let result = match self {
+            Slot::Map(map) => ser::SerializeMap::serialize_key(map, &key),
+            _ => return Err(self.invalid("key outside of map")),
+        };
+
+        self.check(result)
+    }
+
+    fn value(&mut self, value: Erased<'_>) -> Result<(), ErasedError> {
+        let result = match self {
+            Slot::Map(map) => ser::SerializeMap::serialize_value(map, &value),
+            _ => return Err(self.invalid("value outside of map")),
+        };
+
+        self.check(result)
+    }
+
+    fn end(&mut self) -> Result<(), ErasedError> {
+        let result = match mem::replace(self, Slot::Empty) {
+            Slot::Seq(seq) => ser::SerializeSeq::end(seq),
+            Slot::Tuple(tuple) => ser::SerializeTuple::end(tuple),
+            Slot::TupleStruct(tuple_struct) => ser::SerializeTupleStruct::end(tuple_struct),
+            Slot::TupleVariant(tuple_variant) => ser::SerializeTupleVariant::end(tuple_variant),
+            Slot::Map(map) => ser::SerializeMap::end(map),
+            Slot::Struct(r#struct) => ser::SerializeStruct::end(r#struct),
+            Slot::StructVariant(struct_variant) => ser::SerializeStructVariant::end(struct_variant),
+            state => {
+                *self = state;
+                return Err(self.invalid("end outside of compound value"));
+            }
+        };
+
+        self.store(result, Slot::Ok)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        match self {
+            Slot::Serializer(serializer) => serializer.is_human_readable(),
+            _ => true,
+        }
+    }
+}
+
+/// Serializer what erased values serialize themselves into, passes every call to a [Sink].
+pub(crate) struct Erasing<'a>(pub(crate) &'a mut dyn Sink);
+
+impl<'a> Serializer for Erasing<'a> {
+    type Ok = ();
+    type Error = ErasedError;
+
+    type SerializeMap = Self;
+    type SerializeSeq = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+
+    impl_serialize!(
+        value: self.0.call(value_ref),
+        compound: self.0.call(value_ref).map(|()| self),
+        all
+    );
+
+    fn collect_str<T: ?Sized + fmt::Display>(self, value: &T) -> Result<(), ErasedError> {
+        self.0.collect_str(&value)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+impl ser::SerializeSeq for Erasing<'_> {
+    type Ok = ();
+    type Error = ErasedError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ErasedError> {
+        self.0.element(Erased::new(&value))
+    }
+
+    fn end(self) -> Result<(), ErasedError> {
+        self.0.end()
+    }
+}
+
+impl ser::SerializeTuple for Erasing<'_> {
+    type Ok = ();
+    type Error = ErasedError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ErasedError> {
+        self.0.element(Erased::new(&value))
+    }
+
+    fn end(self) -> Result<(), ErasedError> {
+        self.0.end()
+    }
+}
+
+impl ser::SerializeTupleStruct for Erasing<'_> {
+    type Ok = ();
+    type Error = ErasedError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ErasedError> {
+        self.0.element(Erased::new(&value))
+    }
+
+    fn end(self) -> Result<(), ErasedError> {
+        self.0.end()
+    }
+}
+
+impl ser::SerializeTupleVariant for Erasing<'_> {
+    type Ok = ();
+    type Error = ErasedError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ErasedError> {
+        self.0.element(Erased::new(&value))
+    }
+
+    fn end(self) -> Result<(), ErasedError> {
+        self.0.end()
+    }
+}
+
+impl ser::SerializeMap for Erasing<'_> {
+    type Ok = ();
+    type Error = ErasedError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), ErasedError> {
+        self.0.key(Erased::new(&key))
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ErasedError> {
+        self.0.value(Erased::new(&value))
+    }
+
+    fn end(self) -> Result<(), ErasedError> {
+        self.0.end()
+    }
+}
+
+impl ser::SerializeStruct for Erasing<'_> {
+    type Ok = ();
+    type Error = ErasedError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), ErasedError> {
+        self.0.field(key, Erased::new(&value))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), ErasedError> {
+        self.0.skip_field(key)
+    }
+
+    fn end(self) -> Result<(), ErasedError> {
+        self.0.end()
+    }
+}
+
+impl ser::SerializeStructVariant for Erasing<'_> {
+    type Ok = ();
+    type Error = ErasedError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), ErasedError> {
+        self.0.field(key, Erased::new(&value))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), ErasedError> {
+        self.0.skip_field(key)
+    }
+
+    fn end(self) -> Result<(), ErasedError> {
+        self.0.end()
+    }
+}
+
+/// Error of [Erasing]: the error of the real serializer is kept in the [Slot],
+/// custom errors of the erased value carry their message.
+#[derive(Debug)]
+pub(crate) enum ErasedError {
+    Stored,
+    Custom(Message),
+}
+
+impl fmt::Display for ErasedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErasedError::Stored => f.write_str("error of the serializer"),
+            ErasedError::Custom(message) => fmt::Display::fmt(message, f),
+        }
+    }
+}
+
+impl ser::StdError for ErasedError {}
+
+impl ser::Error for ErasedError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        let mut message = Message::default();
+        let _ = fmt::Write::write_fmt(&mut message, format_args!("{}", msg));
+
+        ErasedError::Custom(message)
+    }
+}
+
+#[cfg(feature = "alloc")]
+type Message = alloc::string::String;
+
+/// Message of a custom error without allocation, cut to the capacity at a char boundary.
+#[cfg(not(feature = "alloc"))]
+pub(crate) struct Message {
+    buf: [u8; 96],
+    len: usize,
+}
+
+#[cfg(not(feature = "alloc"))]
+impl Default for Message {
+    fn default() -> Self {
+        Message { buf: [0; 96], len: 0 }
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl Message {
+    fn as_str(&self) -> &str {
+        str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl fmt::Write for Message {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut end = s.len().min(self.buf.len() - self.len);
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        self.buf[self.len..self.len + end].copy_from_slice(&s.as_bytes()[..end]);
+        self.len += end;
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl fmt::Debug for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
@@ -0,0 +1,230 @@
+use crate as impl_serialize;
+use crate::{Erased, ValueKind, ValueRef};
+use serde::ser::{self, Serialize};
+
+/// Serializer written as one function of kind and borrowed arguments of the method.
+///
+/// [`Handled`] implements `Serializer` for every handler. Methods starting compound values
+/// (`seq`, `map`, `struct`, etc.) are handled too, their `Ok` is the state of the compound value:
+/// [`element`](Handler::element), [`field`](Handler::field), [`key`](Handler::key) and
+/// [`value`](Handler::value) add to it and [`end`](Handler::end) turns it into the result.
+/// By default elements, fields and entries are rejected, so only empty compound values are accepted.
+/// `some`, `newtype_struct` and `newtype_variant` pass the inner value as [`ValueRef`],
+/// the handler serializes it by itself.
+/// # Example
+#[doc = include_str!("../docs/handler.md")]
+pub trait Handler: Sized {
+    /// Result of successful serialization.
+    type Ok;
+    /// Error of serialization.
+    type Error: ser::Error;
+
+    /// Handles call of the serialize method of `kind`, which is always `value.kind()`.
+    fn handle(self, kind: ValueKind, value: ValueRef<'_>) -> Result<Self::Ok, Self::Error>;
+
+    /// Adds element of a sequence, tuple, tuple struct or tuple variant started by [`handle`](Handler::handle).
+    fn element(compound: &mut Self::Ok, value: Erased<'_>) -> Result<(), Self::Error> {
+        let _ = compound;
+        Err(reject(value))
+    }
+
+    /// Adds field of a struct or struct variant started by [`handle`](Handler::handle).
+    fn field(compound: &mut Self::Ok, key: &'static str, value: Erased<'_>) -> Result<(), Self::Error> {
+        let _ = (compound, key);
+        Err(reject(value))
+    }
+
+    /// Skips field of a struct or struct variant, does nothing by default.
+    fn skip_field(compound: &mut Self::Ok, key: &'static str) -> Result<(), Self::Error> {
+        let _ = (compound, key);
+        Ok(())
+    }
+
+    /// Adds key of a map entry started by [`handle`](Handler::handle).
+    fn key(compound: &mut Self::Ok, key: Erased<'_>) -> Result<(), Self::Error> {
+        let _ = compound;
+        Err(reject(key))
+    }
+
+    /// Adds value of a map entry started by [`handle`](Handler::handle).
+    fn value(compound: &mut Self::Ok, value: Erased<'_>) -> Result<(), Self::Error> {
+        let _ = compound;
+        Err(reject(value))
+    }
+
+    /// Finishes compound value, returns it as it is by default.
+    fn end(compound: Self::Ok) -> Result<Self::Ok, Self::Error> {
+        Ok(compound)
+    }
+
+    /// Wraps the handler into a serializer.
+    fn serializer(self) -> Handled<Self> {
+        Handled(self)
+    }
+}
+
+fn reject<Error: ser::Error>(value: Erased<'_>) -> Error {
+    let value_type = value.kind().map_or("custom", ValueKind::as_str);
+
+    Error::custom(format_args!("cannot serialize {}", value_type))
+}
+
+/// Serializer what passes arguments of every method to a [Handler].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Handled<H>(pub H);
+
+impl<H: Handler> Handled<H> {
+    fn handle(self, value: ValueRef<'_>) -> Result<H::Ok, H::Error> {
+        self.0.handle(value.kind(), value)
+    }
+
+    fn start(self, value: ValueRef<'_>) -> Result<HandledCompound<H>, H::Error> {
+        self.handle(value).map(|compound| HandledCompound { compound })
+    }
+}
+
+impl<H: Handler> ser::Serializer for Handled<H> {
+    type Ok = H::Ok;
+    type Error = H::Error;
+
+    type SerializeMap = HandledCompound<H>;
+    type SerializeSeq = HandledCompound<H>;
+    type SerializeStruct = HandledCompound<H>;
+    type SerializeStructVariant = HandledCompound<H>;
+    type SerializeTuple = HandledCompound<H>;
+    type SerializeTupleStruct = HandledCompound<H>;
+    type SerializeTupleVariant = HandledCompound<H>;
+
+    impl_serialize!(
+        value: self.handle(value_ref),
+        compound: self.start(value_ref),
+        all
+    );
+
+    #[cfg(not(feature = "alloc"))]
+    fn collect_str<T: ?Sized + core::fmt::Display>(self, _value: &T) -> Result<Self::Ok, Self::Error> {
+        Err(ser::Error::custom("cannot collect str without alloc"))
+    }
+}
+
+/// Compound serializer of [Handled], passes elements, fields and entries to the [Handler].
+pub struct HandledCompound<H: Handler> {
+    compound: H::Ok,
+}
+
+impl<H: Handler> HandledCompound<H> {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), H::Error> {
+        H::element(&mut self.compound, Erased::new(&value))
+    }
+
+    fn field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), H::Error> {
+        H::field(&mut self.compound, key, Erased::new(&value))
+    }
+
+    fn end(self) -> Result<H::Ok, H::Error> {
+        H::end(self.compound)
+    }
+}
+
+impl<H: Handler> ser::SerializeSeq for HandledCompound<H> {
+    type Ok = H::Ok;
+    type Error = H::Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), H::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<H::Ok, H::Error> {
+        HandledCompound::end(self)
+    }
+}
+
+impl<H: Handler> ser::SerializeTuple for HandledCompound<H> {
+    type Ok = H::Ok;
+    type Error = H::Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), H::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<H::Ok, H::Error> {
+        HandledCompound::end(self)
+    }
+}
+
+impl<H: Handler> ser::SerializeTupleStruct for HandledCompound<H> {
+    type Ok = H::Ok;
+    type Error = H::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), H::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<H::Ok, H::Error> {
+        HandledCompound::end(self)
+    }
+}
+
+impl<H: Handler> ser::SerializeTupleVariant for HandledCompound<H> {
+    type Ok = H::Ok;
+    type Error = H::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), H::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<H::Ok, H::Error> {
+        HandledCompound::end(self)
+    }
+}
+
+impl<H: Handler> ser::SerializeMap for HandledCompound<H> {
+    type Ok = H::Ok;
+    type Error = H::Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), H::Error> {
+        H::key(&mut self.compound, Erased::new(&key))
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), H::Error> {
+        H::value(&mut self.compound, Erased::new(&value))
+    }
+
+    fn end(self) -> Result<H::Ok, H::Error> {
+        HandledCompound::end(self)
+    }
+}
+
+impl<H: Handler> ser::SerializeStruct for HandledCompound<H> {
+    type Ok = H::Ok;
+    type Error = H::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), H::Error> {
+        self.field(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), H::Error> {
+        H::skip_field(&mut self.compound, key)
+    }
+
+    fn end(self) -> Result<H::Ok, H::Error> {
+        HandledCompound::end(self)
+    }
+}
+
+impl<H: Handler> ser::SerializeStructVariant for HandledCompound<H> {
+    type Ok = H::Ok;
+    type Error = H::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), H::Error> {
+        self.field(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), H::Error> {
+        H::skip_field(&mut self.compound, key)
+    }
+
+    fn end(self) -> Result<H::Ok, H::Error> {
+        HandledCompound::end(self)
+    }
+}
@@ -0,0 +1,303 @@
+use crate as impl_serialize;
+use crate::{size::display_len, ValueKind, ValueRef};
+use core::{
+    fmt::{self, Write},
+    hash::Hasher,
+};
+use serde::ser::{self, Serialize};
+
+/// Error of [HashingSerializer], only `Serialize` implementations can fail.
+pub type HashError = serde::de::value::Error;
+
+/// Hashes `value` with [HashingSerializer] and [FnvHasher].
+///
+/// The hash doesn't depend on platform, compiler version or size hints of sequences and maps.
+/// # Example
+#[doc = include_str!("../docs/hash.md")]
+pub fn stable_hash<T: ?Sized + Serialize>(value: &T) -> Result<u64, HashError> {
+    let mut hasher = FnvHasher::default();
+
+    value.serialize(HashingSerializer::new(&mut hasher))?;
+    Ok(hasher.finish())
+}
+
+/// 64-bit FNV-1a hasher, used by [stable_hash].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Serializer what feeds the value into a hasher.
+///
+/// Every value starts with its `ValueKind`, followed by little-endian numbers,
+/// length-prefixed strings, bytes and names, and variant indexes. Every compound value ends
+/// with a marker and the number of elements, entries or fields, so structurally different
+/// values feed different bytes. Floats are fed by bits, so `0.0` and `-0.0` hash differently.
+pub struct HashingSerializer<'a, H> {
+    hasher: &'a mut H,
+}
+
+impl<'a, H: Hasher> HashingSerializer<'a, H> {
+    /// Feeds values into `hasher`.
+    pub fn new(hasher: &'a mut H) -> Self {
+        HashingSerializer { hasher }
+    }
+
+    fn len(&mut self, len: usize) {
+        self.hasher.write(&(len as u64).to_le_bytes());
+    }
+
+    fn str(&mut self, v: &str) {
+        self.len(v.len());
+        self.hasher.write(v.as_bytes());
+    }
+
+    fn variant(&mut self, name: &str, variant_index: u32, variant: &str) {
+        self.str(name);
+        self.hasher.write(&variant_index.to_le_bytes());
+        self.str(variant);
+    }
+
+    //feeds everything except inner values and elements
+    fn header(mut self, value: ValueRef<'_>) -> Self {
+        self.hasher.write(&[value.kind() as u8]);
+
+        match value {
+            ValueRef::Bool(v) => self.hasher.write(&[v as u8]),
+            ValueRef::I8(v) => self.hasher.write(&v.to_le_bytes()),
+            ValueRef::I16(v) => self.hasher.write(&v.to_le_bytes()),
+            ValueRef::I32(v) => self.hasher.write(&v.to_le_bytes()),
+            ValueRef::I64(v) => self.hasher.write(&v.to_le_bytes()),
+            ValueRef::I128(v) => self.hasher.write(&v.to_le_bytes()),
+            ValueRef::U8(v) => self.hasher.write(&v.to_le_bytes()),
+            ValueRef::U16(v) => self.hasher.write(&v.to_le_bytes()),
+            ValueRef::U32(v) => self.hasher.write(&v.to_le_bytes()),
+            ValueRef::U64(v) => self.hasher.write(&v.to_le_bytes()),
+            ValueRef::U128(v) => self.hasher.write(&v.to_le_bytes()),
+            ValueRef::F32(v) => self.hasher.write(&v.to_bits().to_le_bytes()),
+            ValueRef::F64(v) => self.hasher.write(&v.to_bits().to_le_bytes()),
+            ValueRef::Char(v) => self.hasher.write(&u32::from(v).to_le_bytes()),
+            ValueRef::Str(v) => self.str(v),
+            ValueRef::Bytes(v) => {
+                self.len(v.len());
+                self.hasher.write(v);
+            }
+            ValueRef::UnitStruct { name }
+            | ValueRef::NewtypeStruct { name, .. }
+            | ValueRef::TupleStruct { name, .. }
+            | ValueRef::Struct { name, .. } => self.str(name),
+            ValueRef::UnitVariant { name, variant_index, variant }
+            | ValueRef::NewtypeVariant { name, variant_index, variant, .. }
+            | ValueRef::TupleVariant { name, variant_index, variant, .. }
+            | ValueRef::StructVariant { name, variant_index, variant, .. } => {
+                self.variant(name, variant_index, variant)
+            }
+            //size hints are ignored, compounds feed the number of elements at the end
+            ValueRef::None
+            | ValueRef::Some(_)
+            | ValueRef::Unit
+            | ValueRef::Seq { .. }
+            | ValueRef::Tuple { .. }
+            | ValueRef::Map { .. } => {}
+        }
+
+        self
+    }
+}
+
+impl<'a, H: Hasher> ser::Serializer for HashingSerializer<'a, H> {
+    type Ok = ();
+    type Error = HashError;
+
+    type SerializeMap = HashingCompound<'a, H>;
+    type SerializeSeq = HashingCompound<'a, H>;
+    type SerializeStruct = HashingCompound<'a, H>;
+    type SerializeStructVariant = HashingCompound<'a, H>;
+    type SerializeTuple = HashingCompound<'a, H>;
+    type SerializeTupleStruct = HashingCompound<'a, H>;
+    type SerializeTupleVariant = HashingCompound<'a, H>;
+
+    impl_serialize!(
+        {
+            self.header(value_ref);
+            Ok(())
+        },
+        [
+            bool,
+            i8, i16, i32, i64, i128,
+            u8, u16, u32, u64, u128,
+            f32, f64,
+            char, str, bytes,
+            none, unit,
+            unit_struct, unit_variant
+        ]
+    );
+
+    impl_serialize!(
+        value.serialize(self.header(value_ref)),
+        [some, newtype_struct, newtype_variant]
+    );
+
+    impl_serialize!(
+        Ok(HashingCompound {
+            hasher: self.header(value_ref).hasher,
+            count: 0,
+        }),
+        compounds
+    );
+
+    //same bytes as serialize_str, without collecting the text
+    fn collect_str<T: ?Sized + fmt::Display>(mut self, value: &T) -> Result<(), HashError> {
+        self.hasher.write(&[ValueKind::Str as u8]);
+        self.len(display_len(value));
+
+        let _ = write!(Feed(self.hasher), "{}", value);
+        Ok(())
+    }
+}
+
+struct Feed<'a, H>(&'a mut H);
+
+impl<'a, H: Hasher> Write for Feed<'a, H> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Compound value of [HashingSerializer].
+pub struct HashingCompound<'a, H> {
+    hasher: &'a mut H,
+    count: usize,
+}
+
+impl<'a, H: Hasher> HashingCompound<'a, H> {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), HashError> {
+        self.count += 1;
+        value.serialize(HashingSerializer::new(&mut *self.hasher))
+    }
+
+    fn field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), HashError> {
+        HashingSerializer::new(&mut *self.hasher).str(key);
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        //after the tag of every element, so elements can't continue past the end
+        self.hasher.write(&[u8::MAX]);
+        HashingSerializer::new(self.hasher).len(self.count);
+        Ok(())
+    }
+}
+
+impl<'a, H: Hasher> ser::SerializeSeq for HashingCompound<'a, H> {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), HashError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        HashingCompound::end(self)
+    }
+}
+
+impl<'a, H: Hasher> ser::SerializeTuple for HashingCompound<'a, H> {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), HashError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        HashingCompound::end(self)
+    }
+}
+
+impl<'a, H: Hasher> ser::SerializeTupleStruct for HashingCompound<'a, H> {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), HashError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        HashingCompound::end(self)
+    }
+}
+
+impl<'a, H: Hasher> ser::SerializeTupleVariant for HashingCompound<'a, H> {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), HashError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        HashingCompound::end(self)
+    }
+}
+
+impl<'a, H: Hasher> ser::SerializeMap for HashingCompound<'a, H> {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), HashError> {
+        self.element(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), HashError> {
+        value.serialize(HashingSerializer::new(&mut *self.hasher))
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        HashingCompound::end(self)
+    }
+}
+
+impl<'a, H: Hasher> ser::SerializeStruct for HashingCompound<'a, H> {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), HashError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        HashingCompound::end(self)
+    }
+}
+
+impl<'a, H: Hasher> ser::SerializeStructVariant for HashingCompound<'a, H> {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), HashError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        HashingCompound::end(self)
+    }
+}
@@ -0,0 +1,252 @@
+use crate as impl_serialize;
+use core::{convert::Infallible, fmt};
+use serde::ser::{self, Serialize};
+
+/// Kind of value in [serde::Serializer](https://docs.rs/serde/latest/serde/trait.Serializer.html),
+/// one per serialize method.
+///
+/// [`as_str`](ValueKind::as_str) returns the `value_type` metavariable of the method.
+/// Discriminants are stable, `kind as u8` is part of [stable_hash](crate::stable_hash) output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum ValueKind {
+    /// `serialize_bool`
+    Bool = 0,
+    /// `serialize_i8`
+    I8 = 1,
+    /// `serialize_i16`
+    I16 = 2,
+    /// `serialize_i32`
+    I32 = 3,
+    /// `serialize_i64`
+    I64 = 4,
+    /// `serialize_i128`
+    I128 = 5,
+    /// `serialize_u8`
+    U8 = 6,
+    /// `serialize_u16`
+    U16 = 7,
+    /// `serialize_u32`
+    U32 = 8,
+    /// `serialize_u64`
+    U64 = 9,
+    /// `serialize_u128`
+    U128 = 10,
+    /// `serialize_f32`
+    F32 = 11,
+    /// `serialize_f64`
+    F64 = 12,
+    /// `serialize_char`
+    Char = 13,
+    /// `serialize_str`
+    Str = 14,
+    /// `serialize_bytes`
+    Bytes = 15,
+    /// `serialize_none`
+    None = 16,
+    /// `serialize_some`
+    Some = 17,
+    /// `serialize_unit`
+    Unit = 18,
+    /// `serialize_unit_struct`
+    UnitStruct = 19,
+    /// `serialize_unit_variant`
+    UnitVariant = 20,
+    /// `serialize_newtype_struct`
+    NewtypeStruct = 21,
+    /// `serialize_newtype_variant`
+    NewtypeVariant = 22,
+    /// `serialize_seq`
+    Seq = 23,
+    /// `serialize_tuple`
+    Tuple = 24,
+    /// `serialize_tuple_struct`
+    TupleStruct = 25,
+    /// `serialize_tuple_variant`
+    TupleVariant = 26,
+    /// `serialize_map`
+    Map = 27,
+    /// `serialize_struct`
+    Struct = 28,
+    /// `serialize_struct_variant`
+    StructVariant = 29,
+}
+
+impl ValueKind {
+    /// Every kind in order of declaration.
+    pub const ALL: [ValueKind; 30] = [
+        ValueKind::Bool,
+        ValueKind::I8,
+        ValueKind::I16,
+        ValueKind::I32,
+        ValueKind::I64,
+        ValueKind::I128,
+        ValueKind::U8,
+        ValueKind::U16,
+        ValueKind::U32,
+        ValueKind::U64,
+        ValueKind::U128,
+        ValueKind::F32,
+        ValueKind::F64,
+        ValueKind::Char,
+        ValueKind::Str,
+        ValueKind::Bytes,
+        ValueKind::None,
+        ValueKind::Some,
+        ValueKind::Unit,
+        ValueKind::UnitStruct,
+        ValueKind::UnitVariant,
+        ValueKind::NewtypeStruct,
+        ValueKind::NewtypeVariant,
+        ValueKind::Seq,
+        ValueKind::Tuple,
+        ValueKind::TupleStruct,
+        ValueKind::TupleVariant,
+        ValueKind::Map,
+        ValueKind::Struct,
+        ValueKind::StructVariant,
+    ];
+
+    /// Returns `value_type` of the kind.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ValueKind::Bool => "bool",
+            ValueKind::I8 => "i8",
+            ValueKind::I16 => "i16",
+            ValueKind::I32 => "i32",
+            ValueKind::I64 => "i64",
+            ValueKind::I128 => "i128",
+            ValueKind::U8 => "u8",
+            ValueKind::U16 => "u16",
+            ValueKind::U32 => "u32",
+            ValueKind::U64 => "u64",
+            ValueKind::U128 => "u128",
+            ValueKind::F32 => "f32",
+            ValueKind::F64 => "f64",
+            ValueKind::Char => "char",
+            ValueKind::Str => "str",
+            ValueKind::Bytes => "bytes",
+            ValueKind::None => "none",
+            ValueKind::Some => "some",
+            ValueKind::Unit => "unit",
+            ValueKind::UnitStruct => "unit_struct",
+            ValueKind::UnitVariant => "unit_variant",
+            ValueKind::NewtypeStruct => "newtype_struct",
+            ValueKind::NewtypeVariant => "newtype_variant",
+            ValueKind::Seq => "seq",
+            ValueKind::Tuple => "tuple",
+            ValueKind::TupleStruct => "tuple_struct",
+            ValueKind::TupleVariant => "tuple_variant",
+            ValueKind::Map => "map",
+            ValueKind::Struct => "struct",
+            ValueKind::StructVariant => "struct_variant",
+        }
+    }
+
+    /// Returns kind what has the `value_type`.
+    pub fn from_value_type(value_type: &str) -> Option<ValueKind> {
+        Some(match value_type {
+            "bool" => ValueKind::Bool,
+            "i8" => ValueKind::I8,
+            "i16" => ValueKind::I16,
+            "i32" => ValueKind::I32,
+            "i64" => ValueKind::I64,
+            "i128" => ValueKind::I128,
+            "u8" => ValueKind::U8,
+            "u16" => ValueKind::U16,
+            "u32" => ValueKind::U32,
+            "u64" => ValueKind::U64,
+            "u128" => ValueKind::U128,
+            "f32" => ValueKind::F32,
+            "f64" => ValueKind::F64,
+            "char" => ValueKind::Char,
+            "str" => ValueKind::Str,
+            "bytes" => ValueKind::Bytes,
+            "none" => ValueKind::None,
+            "some" => ValueKind::Some,
+            "unit" => ValueKind::Unit,
+            "unit_struct" => ValueKind::UnitStruct,
+            "unit_variant" => ValueKind::UnitVariant,
+            "newtype_struct" => ValueKind::NewtypeStruct,
+            "newtype_variant" => ValueKind::NewtypeVariant,
+            "seq" => ValueKind::Seq,
+            "tuple" => ValueKind::Tuple,
+            "tuple_struct" => ValueKind::TupleStruct,
+            "tuple_variant" => ValueKind::TupleVariant,
+            "map" => ValueKind::Map,
+            "struct" => ValueKind::Struct,
+            "struct_variant" => ValueKind::StructVariant,
+            _ => return None,
+        })
+    }
+
+    /// Returns kind of the first serialize method called by `value`,
+    /// or `None` if `value` failed before calling any.
+    pub fn of<T: ?Sized + Serialize>(value: &T) -> Option<ValueKind> {
+        match value.serialize(ValueKindSerializer) {
+            Ok(never) => match never {},
+            Err(Found(kind)) => kind,
+        }
+    }
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+struct ValueKindSerializer;
+
+#[derive(Debug)]
+struct Found(Option<ValueKind>);
+
+impl fmt::Display for Found {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(kind) => kind.fmt(f),
+            None => f.write_str("custom"),
+        }
+    }
+}
+
+impl ser::StdError for Found {}
+
+impl ser::Error for Found {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        Found(None)
+    }
+}
+
+impl ser::Serializer for ValueKindSerializer {
+    type Ok = Infallible;
+    type Error = Found;
+
+    type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+    impl_serialize!(Err(Found(ValueKind::from_value_type(value_type))), [
+        bool,
+        bytes,
+        i8, i16, i32, i64, i128,
+        u8, u16, u32, u64, u128,
+        f32, f64,
+        char,
+        str,
+        none, some, unit,
+        unit_struct, unit_variant,
+        newtype_struct, newtype_variant,
+        seq, map,
+        tuple, tuple_struct, tuple_variant,
+        struct, struct_variant
+    ]);
+
+    fn collect_str<T: ?Sized + fmt::Display>(self, _value: &T) -> Result<Self::Ok, Self::Error> {
+        Err(Found(Some(ValueKind::Str)))
+    }
+}
@@ -3,303 +3,662 @@
 //! # Example
 #![doc = include_str!("../docs/example.md")]
 #![doc = include_str!("../docs/metavariables.md")]
+#![doc = include_str!("../docs/compound.md")]
+#![doc = include_str!("../docs/hooks.md")]
+#![doc = include_str!("../docs/validate.md")]
+#![doc = include_str!("../docs/non_finite.md")]
+#![doc = include_str!("../docs/features.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 pub use unhygienic2::unhygienic;
 
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::{string::String, vec::Vec};
+}
+
+#[macro_use]
+mod deserialize;
+#[macro_use]
+mod visit;
+
 /// Macro for fast and easy implementing serialize methods in 
 /// [serde::Serializer](https://docs.rs/serde/latest/serde/trait.Serializer.html) trait.
 /// # Example
 #[doc = include_str!("../docs/example.md")]
 #[macro_export]
 macro_rules! impl_serialize {
-    ($get_result:expr, [$($type:ident),+]) => {
-        $(
-            impl_serialize!($get_result, $type);
-        )+
+    (impl $($header:tt)+) => {
+        $crate::impl_serialize! { @header [] [] $($header)+ }
     };
 
-    ($get_result:expr, bool) => {
-        impl_serialize::unhygienic! {
-            fn serialize_bool(self, v: bool) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "bool";
+    //generics are collected until `for`, the path right before it is the trait
+    (@header [$($generics:tt)*] [$($path:tt)*] for < $($rest:tt)+) => {
+        $crate::impl_serialize! { @header [$($generics)* $($path)* for <] [] $($rest)+ }
+    };
+    (@header $generics:tt [$($path:tt)+] for $($rest:tt)+) => {
+        $crate::impl_serialize! { @target $generics [$($path)+] [] $($rest)+ }
+    };
+    (@header $generics:tt [$($path:tt)*] $segment:ident $($rest:tt)+) => {
+        $crate::impl_serialize! { @header $generics [$($path)* $segment] $($rest)+ }
+    };
+    (@header $generics:tt [$($path:tt)*] :: $($rest:tt)+) => {
+        $crate::impl_serialize! { @header $generics [$($path)* ::] $($rest)+ }
+    };
+    (@header [$($generics:tt)*] [$($path:tt)*] $token:tt $($rest:tt)+) => {
+        $crate::impl_serialize! { @header [$($generics)* $($path)* $token] [] $($rest)+ }
+    };
 
-                $get_result
-            }
-        }
+    //the type and `where` clause are everything before the body
+    (@target $generics:tt $trait:tt $target:tt { $($body:tt)* }) => {
+        $crate::impl_serialize! { @items [$generics $trait $target] [] [] $($body)* }
+    };
+    (@target $generics:tt $trait:tt [$($target:tt)*] $token:tt $($rest:tt)+) => {
+        $crate::impl_serialize! { @target $generics $trait [$($target)* $token] $($rest)+ }
     };
 
-    ($get_result:expr, i8) => {
-        impl_serialize::unhygienic! {
-            fn serialize_i8(self, v: i8) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "i8";
-                
-                $get_result
-            }
+    (@items $impl:tt [$($items:tt)*] $registrations:tt type $name:ident = $type:ty; $($rest:tt)*) => {
+        $crate::impl_serialize! { @items $impl [$($items)* type $name = $type;] $registrations $($rest)* }
+    };
+    (@items $impl:tt [$($items:tt)*] [$($registrations:tt)*] impl_serialize!($($args:tt)*); $($rest:tt)*) => {
+        $crate::impl_serialize! {
+            @items $impl [$($items)* $crate::impl_serialize!($($args)*);] [$($registrations)* [$($args)*]] $($rest)*
         }
     };
+    (@items $impl:tt [$($items:tt)*] $registrations:tt #[$attr:meta] $($rest:tt)*) => {
+        $crate::impl_serialize! { @items $impl [$($items)* #[$attr]] $registrations $($rest)* }
+    };
+    (@items $impl:tt $items:tt $registrations:tt fn $($rest:tt)+) => {
+        $crate::impl_serialize! { @fn $impl $items $registrations [fn] $($rest)+ }
+    };
+    (
+        @items [[$($generics:tt)*] [$($trait:tt)*] [$($target:tt)*]] [$($items:tt)*] [$([$($args:tt)*])*]
+    ) => {
+        impl $($generics)* $($trait)* for $($target)* {
+            $($items)*
+        }
 
-    ($get_result:expr, i16) => {
-        impl_serialize::unhygienic! {
-            fn serialize_i16(self, v: i16) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "i16";
+        impl $($generics)* $crate::Registry for $($target)* {
+            const IMPLEMENTED_BY_MACRO: &'static [$crate::Registration] = &[
+                $($crate::impl_serialize!(@registration $($args)*)),*
+            ];
+        }
+    };
+    (@items $impl:tt $items:tt $registrations:tt $($rest:tt)+) => {
+        compile_error!(concat!(
+            "expected associated type, `impl_serialize!(...);` or method in the block form of `impl_serialize!`, found `",
+            stringify!($($rest)+),
+            "`"
+        ));
+    };
 
-                $get_result
-            }
+    //the first braces after `fn` are the body
+    (@fn $impl:tt [$($items:tt)*] $registrations:tt [$($signature:tt)*] ($($params:tt)*) -> $ok:ty { $($body:tt)* } $($rest:tt)*) => {
+        $crate::impl_serialize! {
+            @items $impl [$($items)* $($signature)* ($($params)*) -> $ok { $($body)* }] $registrations $($rest)*
         }
     };
+    (@fn $impl:tt [$($items:tt)*] $registrations:tt [$($signature:tt)*] { $($body:tt)* } $($rest:tt)*) => {
+        $crate::impl_serialize! { @items $impl [$($items)* $($signature)* { $($body)* }] $registrations $($rest)* }
+    };
+    (@fn $impl:tt $items:tt $registrations:tt [$($signature:tt)*] $token:tt $($rest:tt)+) => {
+        $crate::impl_serialize! { @fn $impl $items $registrations [$($signature)* $token] $($rest)+ }
+    };
 
-    ($get_result:expr, i32) => {
-        impl_serialize::unhygienic! {
-            fn serialize_i32(self, v: i32) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "i32";
+    (@registration $($args:tt)*) => {
+        $crate::impl_serialize! { @parse register [[] [] [result result] [] [] []] $($args)* }
+    };
 
-                $get_result
-            }
-        }
+    (@parse $mode:ident [[$($attrs:tt)*] $before:tt $after:tt $counted:tt $validate:tt $non_finite:tt] #[$attr:meta] $($rest:tt)*) => {
+        $crate::impl_serialize! { @parse $mode [[$($attrs)* #[$attr]] $before $after $counted $validate $non_finite] $($rest)* }
     };
 
-    ($get_result:expr, i64) => {
-        impl_serialize::unhygienic! {
-            fn serialize_i64(self, v: i64) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "i64";
-                
-                $get_result
-            }
-        }
+    (@parse $mode:ident [$attrs:tt $_before:tt $after:tt $counted:tt $validate:tt $non_finite:tt] before = { $($before:tt)* }, $($rest:tt)*) => {
+        $crate::impl_serialize! { @parse $mode [$attrs [[$($before)*]] $after $counted $validate $non_finite] $($rest)* }
     };
 
-    ($get_result:expr, u8) => {
-        impl_serialize::unhygienic! {
-            fn serialize_u8(self, v: u8) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "u8";
+    (@parse $mode:ident [$attrs:tt $before:tt $_after:tt $counted:tt $validate:tt $non_finite:tt] after = |$result:ident| $after:expr, $($rest:tt)*) => {
+        $crate::impl_serialize! { @parse $mode [$attrs $before [$result $after] $counted $validate $non_finite] $($rest)* }
+    };
 
-                $get_result
-            }
-        }
+    (@parse $mode:ident [$attrs:tt $before:tt $after:tt $_counted:tt $validate:tt $non_finite:tt] counted($stats:expr), $($rest:tt)*) => {
+        $crate::impl_serialize! { @parse $mode [$attrs $before $after [$stats] $validate $non_finite] $($rest)* }
     };
 
-    ($get_result:expr, u16) => {
-        impl_serialize::unhygienic! {
-            fn serialize_u16(self, v: u16) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "u16";
+    (
+        @parse $mode:ident [$attrs:tt $before:tt $after:tt $counted:tt $_validate:tt $non_finite:tt]
+        validate $($check:ident $(($($check_args:tt)*))?)+ => $($rest:tt)*
+    ) => {
+        $crate::impl_serialize! { @parse $mode [$attrs $before $after $counted [$([$check $(($($check_args)*))?])+] $non_finite] $($rest)* }
+    };
 
-                $get_result
-            }
+    (@parse $mode:ident [$attrs:tt $before:tt $after:tt $counted:tt $validate:tt $_non_finite:tt] non_finite = $policy:ident, $($rest:tt)*) => {
+        $crate::impl_serialize! { @parse $mode [$attrs $before $after $counted $validate [$policy]] $($rest)* }
+    };
+
+    (@parse $mode:ident $options:tt value: $value:expr, compound: $compound:expr, $($kinds:tt)+) => {
+        $crate::impl_serialize! {
+            @kinds $mode $options [$value] [$compound]
+            [concat!("value: ", stringify!($value), ", compound: ", stringify!($compound))]
+            $($kinds)+
         }
     };
 
-    ($get_result:expr, u32) => {
-        impl_serialize::unhygienic! {
-            fn serialize_u32(self, v: u32) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "u32";
+    (@parse $mode:ident $options:tt $get_result:expr, $($kinds:tt)+) => {
+        $crate::impl_serialize! { @kinds $mode $options [$get_result] [$get_result] [stringify!($get_result)] $($kinds)+ }
+    };
 
-                $get_result
-            }
+    (@kinds $mode:ident $options:tt $value:tt $compound:tt $label:tt all) => {
+        $crate::impl_serialize! {
+            @kinds $mode $options $value $compound $label [
+                bool,
+                i8, i16, i32, i64, i128,
+                u8, u16, u32, u64, u128,
+                f32, f64,
+                char,
+                str,
+                bytes,
+                none, some, unit,
+                unit_struct, unit_variant,
+                newtype_struct, newtype_variant,
+                seq, tuple, tuple_struct, tuple_variant,
+                map, struct, struct_variant
+            ]
         }
     };
 
-    ($get_result:expr, u64) => {
-        impl_serialize::unhygienic! {
-            fn serialize_u64(self, v: u64) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "u64";
+    (@kinds $mode:ident $options:tt $value:tt $compound:tt $label:tt values) => {
+        $crate::impl_serialize! {
+            @kinds $mode $options $value $compound $label [
+                bool,
+                i8, i16, i32, i64, i128,
+                u8, u16, u32, u64, u128,
+                f32, f64,
+                char,
+                str,
+                bytes,
+                none, some, unit,
+                unit_struct, unit_variant,
+                newtype_struct, newtype_variant
+            ]
+        }
+    };
 
-                $get_result
-            }
+    (@kinds $mode:ident $options:tt $value:tt $compound:tt $label:tt compounds) => {
+        $crate::impl_serialize! {
+            @kinds $mode $options $value $compound $label [
+                seq, tuple, tuple_struct, tuple_variant,
+                map, struct, struct_variant
+            ]
         }
     };
 
-    ($get_result:expr, f32) => {
-        impl_serialize::unhygienic! {
-            fn serialize_f32(self, v: f32) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "f32";
+    (@kinds $mode:ident $options:tt $value:tt $compound:tt $label:tt integers) => {
+        $crate::impl_serialize! {
+            @kinds $mode $options $value $compound $label [
+                i8, i16, i32, i64, i128,
+                u8, u16, u32, u64, u128
+            ]
+        }
+    };
 
-                $get_result
-            }
+    (@kinds $mode:ident $options:tt $value:tt $compound:tt $label:tt floats) => {
+        $crate::impl_serialize! {
+            @kinds $mode $options $value $compound $label [
+                f32, f64
+            ]
         }
     };
 
-    ($get_result:expr, f64) => {
-        impl_serialize::unhygienic! {
-            fn serialize_f64(self, v: f64) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "f64";
+    (@kinds $mode:ident $options:tt $value:tt $compound:tt $label:tt [$($type:ident),+ $(,)?]) => {
+        $crate::impl_serialize! { @group $mode $options $value $compound $label [] $($type)+ }
+    };
 
-                $get_result
-            }
+    (@group $mode:ident $options:tt $value:tt $compound:tt $label:tt [$($done:ident)*] integers $($rest:ident)*) => {
+        $crate::impl_serialize! {
+            @group $mode $options $value $compound $label [$($done)* i8 i16 i32 i64 i128 u8 u16 u32 u64 u128] $($rest)*
         }
     };
 
-    ($get_result:expr, char) => {
-        impl_serialize::unhygienic! {
-            fn serialize_char(self, v: char) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "char";
+    (@group $mode:ident $options:tt $value:tt $compound:tt $label:tt [$($done:ident)*] floats $($rest:ident)*) => {
+        $crate::impl_serialize! { @group $mode $options $value $compound $label [$($done)* f32 f64] $($rest)* }
+    };
 
-                $get_result
-            }
-        }
+    (@group $mode:ident $options:tt $value:tt $compound:tt $label:tt [$($done:ident)*] $type:ident $($rest:ident)*) => {
+        $crate::impl_serialize! { @group $mode $options $value $compound $label [$($done)* $type] $($rest)* }
     };
 
-    ($get_result:expr, str) => {
-        impl_serialize::unhygienic! {
-            fn serialize_str(self, v: &str) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "str";
+    (@group $mode:ident $options:tt $value:tt $compound:tt $label:tt [$($done:ident)+]) => {
+        $crate::impl_serialize! { @parsed $mode $options $value $compound $label [$($done),+] }
+    };
 
-                $get_result
-            }
-        }
+    (@kinds $mode:ident $options:tt $value:tt $compound:tt $label:tt $type:ident) => {
+        $crate::impl_serialize! { @parsed $mode $options $value $compound $label [$type] }
     };
 
-    ($get_result:expr, bytes) => {
-        impl_serialize::unhygienic! {
-            fn serialize_bytes(self, v: &[u8]) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "bytes";
-                
-                $get_result
-            }
+    (@parsed emit $options:tt $value:tt $compound:tt $label:tt [$($type:ident),+]) => {
+        $(
+            $crate::impl_serialize!(@method $options $value $compound, $type);
+        )+
+    };
+
+    (@parsed register $options:tt $value:tt $compound:tt [$label:expr] [$($type:ident),+]) => {
+        $crate::Registration {
+            label: $label,
+            kinds: &[$($crate::impl_serialize!(@kind $type)),+],
         }
     };
 
-    ($get_result:expr, none) => {
-        impl_serialize::unhygienic! {
-            fn serialize_none(self) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "none";
+    (@kind bool) => { $crate::ValueKind::Bool };
+    (@kind i8) => { $crate::ValueKind::I8 };
+    (@kind i16) => { $crate::ValueKind::I16 };
+    (@kind i32) => { $crate::ValueKind::I32 };
+    (@kind i64) => { $crate::ValueKind::I64 };
+    (@kind i128) => { $crate::ValueKind::I128 };
+    (@kind u8) => { $crate::ValueKind::U8 };
+    (@kind u16) => { $crate::ValueKind::U16 };
+    (@kind u32) => { $crate::ValueKind::U32 };
+    (@kind u64) => { $crate::ValueKind::U64 };
+    (@kind u128) => { $crate::ValueKind::U128 };
+    (@kind f32) => { $crate::ValueKind::F32 };
+    (@kind f64) => { $crate::ValueKind::F64 };
+    (@kind char) => { $crate::ValueKind::Char };
+    (@kind str) => { $crate::ValueKind::Str };
+    (@kind bytes) => { $crate::ValueKind::Bytes };
+    (@kind none) => { $crate::ValueKind::None };
+    (@kind some) => { $crate::ValueKind::Some };
+    (@kind unit) => { $crate::ValueKind::Unit };
+    (@kind unit_struct) => { $crate::ValueKind::UnitStruct };
+    (@kind unit_variant) => { $crate::ValueKind::UnitVariant };
+    (@kind newtype_struct) => { $crate::ValueKind::NewtypeStruct };
+    (@kind newtype_variant) => { $crate::ValueKind::NewtypeVariant };
+    (@kind seq) => { $crate::ValueKind::Seq };
+    (@kind tuple) => { $crate::ValueKind::Tuple };
+    (@kind tuple_struct) => { $crate::ValueKind::TupleStruct };
+    (@kind tuple_variant) => { $crate::ValueKind::TupleVariant };
+    (@kind map) => { $crate::ValueKind::Map };
+    (@kind struct) => { $crate::ValueKind::Struct };
+    (@kind struct_variant) => { $crate::ValueKind::StructVariant };
+
+    (@check $v:ident range($range:expr)) => { ($range).contains(&$v) };
+    (@check $v:ident max_len($max:expr)) => { $v.len() <= $max };
+    (@check $v:ident non_empty) => { !$v.is_empty() };
+    (@check $v:ident finite) => { $v.is_finite() };
+    (@check $v:ident $($constraint:tt)*) => {
+        compile_error!(concat!("unknown validate constraint `", stringify!($($constraint)*), "`"))
+    };
 
-                $get_result
-            }
-        }
+    (@non_finite f32 [$policy:ident] $self:ident $v:ident $value_type:ident $rest:block) => {
+        $crate::impl_serialize!(@float f32 $policy $self $v $value_type $rest)
     };
+    (@non_finite f64 [$policy:ident] $self:ident $v:ident $value_type:ident $rest:block) => {
+        $crate::impl_serialize!(@float f64 $policy $self $v $value_type $rest)
+    };
+    (@non_finite $kind:ident $policy:tt $self:ident $v:ident $value_type:ident $rest:block) => { $rest };
 
-    ($get_result:expr, unit) => {
-        impl_serialize::unhygienic! {
-            fn serialize_unit(self) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "unit";
+    (@float $float:ident reject $self:ident $v:ident $value_type:ident $rest:block) => {
+        if !$v.is_finite() {
+            Err($crate::violation($value_type, &$v, "finite"))
+        } else $rest
+    };
+    (@float $float:ident null $self:ident $v:ident $value_type:ident $rest:block) => {
+        if !$v.is_finite() { $self.serialize_none() } else $rest
+    };
+    (@float $float:ident unit $self:ident $v:ident $value_type:ident $rest:block) => {
+        if !$v.is_finite() { $self.serialize_unit() } else $rest
+    };
+    (@float $float:ident string $self:ident $v:ident $value_type:ident $rest:block) => {
+        if $v.is_nan() {
+            $self.serialize_str("NaN")
+        } else if $v.is_infinite() {
+            $self.serialize_str(if $v > 0.0 { "inf" } else { "-inf" })
+        } else $rest
+    };
+    (@float $float:ident saturate $self:ident $v:ident $value_type:ident $rest:block) => {{
+        let $v = if $v.is_nan() { 0.0 } else { $v.clamp($float::MIN, $float::MAX) };
+        $rest
+    }};
+    (@float $float:ident $policy:ident $self:ident $v:ident $value_type:ident $rest:block) => {
+        compile_error!(concat!("unknown non_finite policy `", stringify!($policy), "`"))
+    };
 
-                $get_result
+    (
+        @fn [[$(#[$attr:meta])*] [$([$($before:tt)*])?] [$result:ident $after:expr] [$($stats:expr)?] [$([$check:ident $($check_args:tt)?])*] $non_finite:tt]
+        $kind:ident, $get_result:expr, $value_type:literal, [$($signature:tt)*] -> $ok:ty, $unexpected:expr, $value_ref:expr
+    ) => {
+        $crate::unhygienic! {
+            $(#[$attr])*
+            $($signature)* -> core::result::Result<$ok, Self::Error> {
+                let value_type = $value_type;
+                let unexpected = $unexpected;
+                let value_ref = $value_ref;
+
+                $(($stats).record(impl_serialize::impl_serialize!(@kind $kind));)?
+                $($($before)*;)?
+
+                let $result: core::result::Result<$ok, Self::Error> = impl_serialize::impl_serialize!(
+                    @non_finite $kind $non_finite self v value_type {
+                        $(
+                            if !impl_serialize::impl_serialize!(@check v $check $($check_args)?) {
+                                Err(impl_serialize::violation(value_type, &v, stringify!($check $($check_args)?)))
+                            } else
+                        )* {
+                            $get_result
+                        }
+                    }
+                );
+                $after
             }
         }
     };
 
-    ($get_result:expr, some) => {
-        impl_serialize::unhygienic! {
-            fn serialize_some<T: ?Sized + serde::ser::Serialize>(self, value: &T) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "some";
+    (@method $options:tt [$get_result:expr] $compound:tt, bool) => {
+        $crate::impl_serialize!(@fn $options bool, $get_result, "bool",
+            [fn serialize_bool(self, v: bool)] -> Self::Ok,
+            serde::de::Unexpected::Bool(v),
+            impl_serialize::ValueRef::Bool(v)
+        );
+    };
 
-                $get_result
-            }
-        }
+    (@method $options:tt [$get_result:expr] $compound:tt, i8) => {
+        $crate::impl_serialize!(@fn $options i8, $get_result, "i8",
+            [fn serialize_i8(self, v: i8)] -> Self::Ok,
+            serde::de::Unexpected::Signed(v as i64),
+            impl_serialize::ValueRef::I8(v)
+        );
     };
 
-    ($get_result:expr, unit_struct) => {
-        impl_serialize::unhygienic! {
-            fn serialize_unit_struct(self, name: &'static str) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "unit_struct";
+    (@method $options:tt [$get_result:expr] $compound:tt, i16) => {
+        $crate::impl_serialize!(@fn $options i16, $get_result, "i16",
+            [fn serialize_i16(self, v: i16)] -> Self::Ok,
+            serde::de::Unexpected::Signed(v as i64),
+            impl_serialize::ValueRef::I16(v)
+        );
+    };
 
-                $get_result
-            }
-        }
+    (@method $options:tt [$get_result:expr] $compound:tt, i32) => {
+        $crate::impl_serialize!(@fn $options i32, $get_result, "i32",
+            [fn serialize_i32(self, v: i32)] -> Self::Ok,
+            serde::de::Unexpected::Signed(v as i64),
+            impl_serialize::ValueRef::I32(v)
+        );
     };
 
-    ($get_result:expr, unit_variant) => {
-        impl_serialize::unhygienic! {
-            fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "unit_variant";
-                
-                $get_result
-            }
-        }
+    (@method $options:tt [$get_result:expr] $compound:tt, i64) => {
+        $crate::impl_serialize!(@fn $options i64, $get_result, "i64",
+            [fn serialize_i64(self, v: i64)] -> Self::Ok,
+            serde::de::Unexpected::Signed(v),
+            impl_serialize::ValueRef::I64(v)
+        );
     };
 
-    ($get_result:expr, newtype_struct) => {
-        impl_serialize::unhygienic! {
-            fn serialize_newtype_struct<T: ?Sized + serde::ser::Serialize>(self, name: &'static str, value: &T) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "newtype_struct";
-                
-                $get_result
-            }
-        }
+    (@method $options:tt [$get_result:expr] $compound:tt, i128) => {
+        $crate::impl_serialize!(@fn $options i128, $get_result, "i128",
+            [fn serialize_i128(self, v: i128)] -> Self::Ok,
+            serde::de::Unexpected::Other("i128"),
+            impl_serialize::ValueRef::I128(v)
+        );
     };
 
-    ($get_result:expr, newtype_variant) => {
-        impl_serialize::unhygienic! {
-            fn serialize_newtype_variant<T: ?Sized + serde::ser::Serialize>(self, name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> core::result::Result<Self::Ok, Self::Error> {
-                let value_type = "newtype_variant";
-                
-                $get_result
-            }
-        }
+    (@method $options:tt [$get_result:expr] $compound:tt, u8) => {
+        $crate::impl_serialize!(@fn $options u8, $get_result, "u8",
+            [fn serialize_u8(self, v: u8)] -> Self::Ok,
+            serde::de::Unexpected::Unsigned(v as u64),
+            impl_serialize::ValueRef::U8(v)
+        );
     };
 
-    ($get_result:expr, seq) => {
-        impl_serialize::unhygienic! {
-            fn serialize_seq(self, len: Option<usize>) -> core::result::Result<Self::SerializeSeq, Self::Error> {
-                let value_type = "seq";
-                
-                $get_result
-            }
-        }
+    (@method $options:tt [$get_result:expr] $compound:tt, u16) => {
+        $crate::impl_serialize!(@fn $options u16, $get_result, "u16",
+            [fn serialize_u16(self, v: u16)] -> Self::Ok,
+            serde::de::Unexpected::Unsigned(v as u64),
+            impl_serialize::ValueRef::U16(v)
+        );
     };
 
-    ($get_result:expr, tuple) => {
-        impl_serialize::unhygienic! {
-            fn serialize_tuple(self, len: usize) -> core::result::Result<Self::SerializeTuple, Self::Error> {
-                let value_type = "tuple";
+    (@method $options:tt [$get_result:expr] $compound:tt, u32) => {
+        $crate::impl_serialize!(@fn $options u32, $get_result, "u32",
+            [fn serialize_u32(self, v: u32)] -> Self::Ok,
+            serde::de::Unexpected::Unsigned(v as u64),
+            impl_serialize::ValueRef::U32(v)
+        );
+    };
 
-                $get_result
-            }
-        }
+    (@method $options:tt [$get_result:expr] $compound:tt, u64) => {
+        $crate::impl_serialize!(@fn $options u64, $get_result, "u64",
+            [fn serialize_u64(self, v: u64)] -> Self::Ok,
+            serde::de::Unexpected::Unsigned(v),
+            impl_serialize::ValueRef::U64(v)
+        );
     };
 
-    ($get_result:expr, tuple_struct) => {
-        impl_serialize::unhygienic! {
-            fn serialize_tuple_struct(self, name: &'static str, len: usize) -> core::result::Result<Self::SerializeTupleStruct, Self::Error> {
-                let value_type = "tuple_struct";
+    (@method $options:tt [$get_result:expr] $compound:tt, u128) => {
+        $crate::impl_serialize!(@fn $options u128, $get_result, "u128",
+            [fn serialize_u128(self, v: u128)] -> Self::Ok,
+            serde::de::Unexpected::Other("u128"),
+            impl_serialize::ValueRef::U128(v)
+        );
+    };
 
-                $get_result
-            }
-        }
+    (@method $options:tt [$get_result:expr] $compound:tt, f32) => {
+        $crate::impl_serialize!(@fn $options f32, $get_result, "f32",
+            [fn serialize_f32(self, v: f32)] -> Self::Ok,
+            serde::de::Unexpected::Float(v as f64),
+            impl_serialize::ValueRef::F32(v)
+        );
     };
 
-    ($get_result:expr, tuple_variant) => {
-        impl_serialize::unhygienic! {
-            fn serialize_tuple_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> core::result::Result<Self::SerializeTupleVariant, Self::Error> {
-                let value_type = "tuple_variant";
+    (@method $options:tt [$get_result:expr] $compound:tt, f64) => {
+        $crate::impl_serialize!(@fn $options f64, $get_result, "f64",
+            [fn serialize_f64(self, v: f64)] -> Self::Ok,
+            serde::de::Unexpected::Float(v),
+            impl_serialize::ValueRef::F64(v)
+        );
+    };
 
-                $get_result
-            }
-        }
+    (@method $options:tt [$get_result:expr] $compound:tt, char) => {
+        $crate::impl_serialize!(@fn $options char, $get_result, "char",
+            [fn serialize_char(self, v: char)] -> Self::Ok,
+            serde::de::Unexpected::Char(v),
+            impl_serialize::ValueRef::Char(v)
+        );
+    };
+
+    (@method $options:tt [$get_result:expr] $compound:tt, str) => {
+        $crate::impl_serialize!(@fn $options str, $get_result, "str",
+            [fn serialize_str(self, v: &str)] -> Self::Ok,
+            serde::de::Unexpected::Str(v),
+            impl_serialize::ValueRef::Str(v)
+        );
     };
 
-    ($get_result:expr, map) => {
-        impl_serialize::unhygienic! {
-            fn serialize_map(self, len: Option<usize>) -> core::result::Result<Self::SerializeMap, Self::Error> {
-                let value_type = "map";
+    (@method $options:tt [$get_result:expr] $compound:tt, bytes) => {
+        $crate::impl_serialize!(@fn $options bytes, $get_result, "bytes",
+            [fn serialize_bytes(self, v: &[u8])] -> Self::Ok,
+            serde::de::Unexpected::Bytes(v),
+            impl_serialize::ValueRef::Bytes(v)
+        );
+    };
 
-                $get_result
-            }
-        }
+    (@method $options:tt [$get_result:expr] $compound:tt, none) => {
+        $crate::impl_serialize!(@fn $options none, $get_result, "none",
+            [fn serialize_none(self)] -> Self::Ok,
+            serde::de::Unexpected::Option,
+            impl_serialize::ValueRef::None
+        );
     };
 
-    ($get_result:expr, struct) => {
-        impl_serialize::unhygienic! {
-            fn serialize_struct(self, name: &'static str, len: usize) -> core::result::Result<Self::SerializeStruct, Self::Error> {
-                let value_type = "struct";
+    (@method $options:tt [$get_result:expr] $compound:tt, some) => {
+        $crate::impl_serialize!(@fn $options some, $get_result, "some",
+            [fn serialize_some<T: ?Sized + serde::ser::Serialize>(self, value: &T)] -> Self::Ok,
+            serde::de::Unexpected::Option,
+            impl_serialize::ValueRef::Some(impl_serialize::Erased::new(&value))
+        );
+    };
 
-                $get_result
-            }
-        }
+    (@method $options:tt [$get_result:expr] $compound:tt, unit) => {
+        $crate::impl_serialize!(@fn $options unit, $get_result, "unit",
+            [fn serialize_unit(self)] -> Self::Ok,
+            serde::de::Unexpected::Unit,
+            impl_serialize::ValueRef::Unit
+        );
     };
 
-    ($get_result:expr, struct_variant) => {
-        impl_serialize::unhygienic! {
-            fn serialize_struct_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> core::result::Result<Self::SerializeStructVariant, Self::Error> {
-                let value_type = "struct_variant";
+    (@method $options:tt [$get_result:expr] $compound:tt, unit_struct) => {
+        $crate::impl_serialize!(@fn $options unit_struct, $get_result, "unit_struct",
+            [fn serialize_unit_struct(self, name: &'static str)] -> Self::Ok,
+            serde::de::Unexpected::Unit,
+            impl_serialize::ValueRef::UnitStruct { name }
+        );
+    };
 
-                $get_result
-            }
-        }
+    (@method $options:tt [$get_result:expr] $compound:tt, unit_variant) => {
+        $crate::impl_serialize!(@fn $options unit_variant, $get_result, "unit_variant",
+            [fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str)] -> Self::Ok,
+            serde::de::Unexpected::UnitVariant,
+            impl_serialize::ValueRef::UnitVariant { name, variant_index, variant }
+        );
+    };
+
+    (@method $options:tt [$get_result:expr] $compound:tt, newtype_struct) => {
+        $crate::impl_serialize!(@fn $options newtype_struct, $get_result, "newtype_struct",
+            [fn serialize_newtype_struct<T: ?Sized + serde::ser::Serialize>(self, name: &'static str, value: &T)] -> Self::Ok,
+            serde::de::Unexpected::NewtypeStruct,
+            impl_serialize::ValueRef::NewtypeStruct { name, value: impl_serialize::Erased::new(&value) }
+        );
+    };
+
+    (@method $options:tt [$get_result:expr] $compound:tt, newtype_variant) => {
+        $crate::impl_serialize!(@fn $options newtype_variant, $get_result, "newtype_variant",
+            [fn serialize_newtype_variant<T: ?Sized + serde::ser::Serialize>(self, name: &'static str, variant_index: u32, variant: &'static str, value: &T)] -> Self::Ok,
+            serde::de::Unexpected::NewtypeVariant,
+            impl_serialize::ValueRef::NewtypeVariant { name, variant_index, variant, value: impl_serialize::Erased::new(&value) }
+        );
+    };
+
+    (@method $options:tt $value:tt [$get_result:expr], seq) => {
+        $crate::impl_serialize!(@fn $options seq, $get_result, "seq",
+            [fn serialize_seq(self, len: Option<usize>)] -> Self::SerializeSeq,
+            serde::de::Unexpected::Seq,
+            impl_serialize::ValueRef::Seq { len }
+        );
+    };
+
+    (@method $options:tt $value:tt [$get_result:expr], tuple) => {
+        $crate::impl_serialize!(@fn $options tuple, $get_result, "tuple",
+            [fn serialize_tuple(self, len: usize)] -> Self::SerializeTuple,
+            serde::de::Unexpected::Seq,
+            impl_serialize::ValueRef::Tuple { len }
+        );
+    };
+
+    (@method $options:tt $value:tt [$get_result:expr], tuple_struct) => {
+        $crate::impl_serialize!(@fn $options tuple_struct, $get_result, "tuple_struct",
+            [fn serialize_tuple_struct(self, name: &'static str, len: usize)] -> Self::SerializeTupleStruct,
+            serde::de::Unexpected::Seq,
+            impl_serialize::ValueRef::TupleStruct { name, len }
+        );
+    };
+
+    (@method $options:tt $value:tt [$get_result:expr], tuple_variant) => {
+        $crate::impl_serialize!(@fn $options tuple_variant, $get_result, "tuple_variant",
+            [fn serialize_tuple_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize)] -> Self::SerializeTupleVariant,
+            serde::de::Unexpected::TupleVariant,
+            impl_serialize::ValueRef::TupleVariant { name, variant_index, variant, len }
+        );
+    };
+
+    (@method $options:tt $value:tt [$get_result:expr], map) => {
+        $crate::impl_serialize!(@fn $options map, $get_result, "map",
+            [fn serialize_map(self, len: Option<usize>)] -> Self::SerializeMap,
+            serde::de::Unexpected::Map,
+            impl_serialize::ValueRef::Map { len }
+        );
+    };
+
+    (@method $options:tt $value:tt [$get_result:expr], struct) => {
+        $crate::impl_serialize!(@fn $options struct, $get_result, "struct",
+            [fn serialize_struct(self, name: &'static str, len: usize)] -> Self::SerializeStruct,
+            serde::de::Unexpected::Map,
+            impl_serialize::ValueRef::Struct { name, len }
+        );
+    };
+
+    (@method $options:tt $value:tt [$get_result:expr], struct_variant) => {
+        $crate::impl_serialize!(@fn $options struct_variant, $get_result, "struct_variant",
+            [fn serialize_struct_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize)] -> Self::SerializeStructVariant,
+            serde::de::Unexpected::StructVariant,
+            impl_serialize::ValueRef::StructVariant { name, variant_index, variant, len }
+        );
+    };
+
+    ($($args:tt)*) => {
+        $crate::impl_serialize! { @parse emit [[] [] [result result] [] [] []] $($args)* }
     };
 }
 
-#[cfg(test)]
+#[macro_use]
+mod conformance;
+#[cfg(feature = "alloc")]
+mod diff;
+mod erased;
+mod handler;
+mod hash;
+mod kind;
+#[cfg(feature = "alloc")]
+mod map_key;
+#[cfg(feature = "alloc")]
+mod path;
+#[cfg(feature = "alloc")]
+#[macro_use]
+mod recording;
+mod registry;
+mod rejecting;
+#[cfg(feature = "alloc")]
+mod shape;
+mod size;
+mod stats;
+mod validate;
+#[cfg(feature = "alloc")]
+mod value;
+#[cfg(feature = "alloc")]
+mod value_de;
+mod value_ref;
+#[cfg(feature = "alloc")]
+mod value_ser;
+
+pub use conformance::check_serializer;
+#[cfg(feature = "alloc")]
+pub use diff::{diff, Change, Difference};
+pub use handler::{Handled, HandledCompound, Handler};
+pub use hash::{stable_hash, FnvHasher, HashError, HashingCompound, HashingSerializer};
+pub use kind::ValueKind;
+#[cfg(feature = "alloc")]
+pub use map_key::MapKeySerializer;
+#[cfg(feature = "alloc")]
+pub use path::{serialize_with_path, Path, PathError, Segment, Track, WithPath, WithPathCompound};
+#[cfg(feature = "alloc")]
+pub use recording::{check_events, to_events, Event, RecordingCompound, RecordingSerializer};
+pub use registry::{Registration, Registry};
+pub use rejecting::Rejecting;
+#[cfg(feature = "alloc")]
+pub use shape::{to_shape, Field, Shape, ShapeCompound, ShapeSerializer, Variant};
+pub use size::{estimate_size, Cost, CostModel, SizeCompound, SizeError, SizeEstimator};
+pub use stats::Stats;
+pub use validate::violation;
+#[cfg(feature = "alloc")]
+pub use value::{to_value, Value, ValueError};
+#[cfg(feature = "alloc")]
+pub use value_de::from_value;
+pub use value_ref::{Erased, ValueRef};
+#[cfg(feature = "alloc")]
+pub use value_ser::{ValueCompound, ValueSerializer};
+
+#[cfg(all(test, feature = "std"))]
 mod tests;
 
 #[doc = include_str!("../README.md")]
@@ -0,0 +1,110 @@
+use crate as impl_serialize;
+use crate::ValueError;
+use alloc::{
+    borrow::{Cow, ToOwned},
+    string::ToString,
+};
+use serde::ser;
+
+/// Serializer of map keys for formats what need string keys.
+///
+/// Strings and chars are always accepted, integers by default, unit variants and bools if enabled.
+/// Newtype structs are serialized as their value, everything else is rejected.
+/// Unit variant names are borrowed, other keys are owned.
+/// # Example
+#[doc = include_str!("../docs/map_key.md")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapKeySerializer {
+    integers: bool,
+    unit_variants: bool,
+    bools: bool,
+}
+
+impl Default for MapKeySerializer {
+    fn default() -> Self {
+        MapKeySerializer {
+            integers: true,
+            unit_variants: false,
+            bools: false,
+        }
+    }
+}
+
+impl MapKeySerializer {
+    /// Accepts strings, chars and integers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether integers are accepted as their decimal text.
+    pub fn integers(self, integers: bool) -> Self {
+        MapKeySerializer { integers, ..self }
+    }
+
+    /// Whether unit variants are accepted as their names.
+    pub fn unit_variants(self, unit_variants: bool) -> Self {
+        MapKeySerializer { unit_variants, ..self }
+    }
+
+    /// Whether bools are accepted as `"true"` and `"false"`.
+    pub fn bools(self, bools: bool) -> Self {
+        MapKeySerializer { bools, ..self }
+    }
+
+    fn accept(enabled: bool, key: impl FnOnce() -> Cow<'static, str>, value_type: &str) -> Result<Cow<'static, str>, ValueError> {
+        if enabled {
+            Ok(key())
+        } else {
+            Err(reject(value_type))
+        }
+    }
+}
+
+fn reject(value_type: &str) -> ValueError {
+    ser::Error::custom(format_args!("cannot use {} as map key", value_type))
+}
+
+impl ser::Serializer for MapKeySerializer {
+    type Ok = Cow<'static, str>;
+    type Error = ValueError;
+
+    type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+    impl_serialize!(Ok(Cow::Owned(v.to_owned())), str);
+
+    impl_serialize!(Ok(Cow::Owned(v.to_string())), char);
+
+    impl_serialize!(
+        MapKeySerializer::accept(self.integers, || Cow::Owned(v.to_string()), value_type),
+        [integers]
+    );
+
+    impl_serialize!(
+        MapKeySerializer::accept(self.bools, || Cow::Borrowed(if v { "true" } else { "false" }), value_type),
+        bool
+    );
+
+    impl_serialize!(
+        MapKeySerializer::accept(self.unit_variants, || Cow::Borrowed(variant), value_type),
+        unit_variant
+    );
+
+    impl_serialize!(value.serialize(self), newtype_struct);
+
+    impl_serialize!(Err(reject(value_type)), [
+        f32, f64,
+        bytes,
+        none, some, unit,
+        unit_struct,
+        newtype_variant,
+        seq, map,
+        tuple, tuple_struct, tuple_variant,
+        struct, struct_variant
+    ]);
+}
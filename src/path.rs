@@ -0,0 +1,561 @@
+use crate as impl_serialize;
+use crate::ValueRef;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{cell::RefCell, fmt};
+use serde::ser::{self, Serialize, Serializer};
+
+/// Serializes `value` with [WithPath], errors are annotated with path of the value what failed.
+/// # Example
+#[doc = include_str!("../docs/path.md")]
+pub fn serialize_with_path<T, S>(value: &T, serializer: S) -> Result<S::Ok, PathError<S::Error>>
+where
+    T: ?Sized + Serialize,
+    S: Serializer,
+{
+    value.serialize(WithPath::new(serializer, &Track::new()))
+}
+
+/// Path from the root value through struct fields, map keys, sequence indexes and enum variants.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Path(Vec<Segment>);
+
+/// One step of [Path].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Segment {
+    /// Field of a struct or struct variant, `.name`.
+    Field(&'static str),
+    /// Element of a sequence, tuple or tuple variant, `[2]`.
+    Index(usize),
+    /// Value of a map entry with string, integer, char or bool key, `["key"]`.
+    Key(String),
+    /// Value of a map entry with other key, `[?]`.
+    UnknownKey,
+    /// Enum variant, `::Variant`.
+    Variant(&'static str),
+}
+
+impl Path {
+    /// Segments from the root.
+    pub fn segments(&self) -> &[Segment] {
+        &self.0
+    }
+}
+
+/// `.servers[2].port`, `.` for the root.
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            return f.write_str(".");
+        }
+
+        for segment in &self.0 {
+            match segment {
+                Segment::Field(name) => write!(f, ".{}", name)?,
+                Segment::Index(index) => write!(f, "[{}]", index)?,
+                Segment::Key(key) => write!(f, "[{}]", key)?,
+                Segment::UnknownKey => f.write_str("[?]")?,
+                Segment::Variant(variant) => write!(f, "::{}", variant)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Error of the inner serializer with path of the value what failed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathError<E> {
+    /// Path of the value what failed.
+    pub path: Path,
+    /// Error of the inner serializer.
+    pub error: E,
+}
+
+impl<E: fmt::Display> fmt::Display for PathError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.error)
+    }
+}
+
+/// Doesn't return the inner error as `source`, so it can wrap errors what borrow,
+/// which every `Serializer` nested by [WithPath] can have.
+impl<E: ser::StdError> ser::StdError for PathError<E> {}
+
+/// Custom errors of the serialized value, the path is added by [WithPath] which returns them.
+impl<E: ser::Error> ser::Error for PathError<E> {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        PathError {
+            path: Path::default(),
+            error: E::custom(msg),
+        }
+    }
+}
+
+/// Path of the deepest value what failed, shared by [WithPath] and its nested serializers.
+///
+/// Nested serializers push their segment onto one stack while the value is serialized,
+/// the path is copied from the stack only when something fails. Every call of the inner
+/// serializer forgets the failure of the previous one, so an error what was caught by
+/// the value and ignored doesn't name the path of a later one.
+#[derive(Debug, Default)]
+pub struct Track {
+    stack: RefCell<Vec<Segment>>,
+    failed: RefCell<Option<Path>>,
+}
+
+impl Track {
+    /// Track without a failure.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Path of the value what failed, `None` if nothing failed.
+    pub fn path(&self) -> Option<Path> {
+        self.failed.borrow().clone()
+    }
+
+    /// Annotates `error` with the path of the failure, the root if nothing failed.
+    pub fn annotate<E>(&self, error: E) -> PathError<E> {
+        PathError {
+            path: self.path().unwrap_or_default(),
+            error,
+        }
+    }
+
+    fn push(&self, segment: Segment) {
+        self.stack.borrow_mut().push(segment);
+    }
+
+    fn pop(&self) -> Option<Segment> {
+        self.stack.borrow_mut().pop()
+    }
+
+    //the map key being serialized is on top of the stack
+    fn name_key(&self, key: ValueRef<'_>) {
+        if let (Some(name), Some(top)) = (key_name(key), self.stack.borrow_mut().last_mut()) {
+            *top = Segment::Key(name);
+        }
+    }
+
+    fn fail(&self) {
+        *self.failed.borrow_mut() = Some(Path(self.stack.borrow().clone()));
+    }
+
+    //errors propagate from the deepest value, so the path recorded first by this call is kept
+    fn call<T, E>(&self, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        self.failed.replace(None);
+        let result = f();
+
+        match result {
+            Ok(_) => {
+                self.failed.replace(None);
+            }
+            Err(_) if self.failed.borrow().is_none() => self.fail(),
+            Err(_) => {}
+        }
+
+        result
+    }
+}
+
+/// Serializer what passes every call to the inner serializer, wrapping nested values,
+/// and annotates its errors with the path of the value what failed, recorded into [Track].
+pub struct WithPath<'a, S> {
+    inner: S,
+    track: &'a Track,
+    key: bool,
+}
+
+impl<'a, S> WithPath<'a, S> {
+    /// Wraps `inner`, failures are recorded into `track`.
+    pub fn new(inner: S, track: &'a Track) -> Self {
+        WithPath {
+            inner,
+            track,
+            key: false,
+        }
+    }
+}
+
+impl<'a, S: Serializer> WithPath<'a, S> {
+    fn run<T>(self, f: impl FnOnce(S) -> Result<T, S::Error>) -> Result<T, PathError<S::Error>> {
+        let (inner, track) = (self.inner, self.track);
+
+        track.call(|| f(inner)).map_err(|error| track.annotate(error))
+    }
+
+    fn within<T>(self, segment: Segment, f: impl FnOnce(S) -> Result<T, S::Error>) -> Result<T, PathError<S::Error>> {
+        let track = self.track;
+
+        track.push(segment);
+        let result = self.run(f);
+        track.pop();
+        result
+    }
+
+    fn scalar(self, value: ValueRef<'_>) -> Result<S::Ok, PathError<S::Error>> {
+        if self.key {
+            self.track.name_key(value);
+        }
+
+        self.run(|inner| forward(inner, value))
+    }
+
+    fn start<C>(
+        self,
+        variant: Option<&'static str>,
+        f: impl FnOnce(S) -> Result<C, S::Error>,
+    ) -> Result<WithPathCompound<'a, C>, PathError<S::Error>> {
+        let track = self.track;
+
+        self.run(f).map(|inner| WithPathCompound {
+            inner,
+            track,
+            variant,
+            index: 0,
+            key: None,
+        })
+    }
+
+    fn nested<'b, T: ?Sized>(&self, value: &'b T) -> Tracked<'a, 'b, T> {
+        Tracked {
+            value,
+            track: self.track,
+            key: self.key,
+        }
+    }
+}
+
+impl<'a, S: Serializer> Serializer for WithPath<'a, S> {
+    type Ok = S::Ok;
+    type Error = PathError<S::Error>;
+
+    type SerializeMap = WithPathCompound<'a, S::SerializeMap>;
+    type SerializeSeq = WithPathCompound<'a, S::SerializeSeq>;
+    type SerializeStruct = WithPathCompound<'a, S::SerializeStruct>;
+    type SerializeStructVariant = WithPathCompound<'a, S::SerializeStructVariant>;
+    type SerializeTuple = WithPathCompound<'a, S::SerializeTuple>;
+    type SerializeTupleStruct = WithPathCompound<'a, S::SerializeTupleStruct>;
+    type SerializeTupleVariant = WithPathCompound<'a, S::SerializeTupleVariant>;
+
+    impl_serialize!(
+        self.scalar(value_ref),
+        [
+            bool,
+            i8, i16, i32, i64, i128,
+            u8, u16, u32, u64, u128,
+            f32, f64,
+            char, str, bytes,
+            none, unit,
+            unit_struct, unit_variant
+        ]
+    );
+
+    impl_serialize!(
+        {
+            let value = self.nested(value);
+            self.run(|inner| inner.serialize_some(&value))
+        },
+        some
+    );
+
+    impl_serialize!(
+        {
+            let value = self.nested(value);
+            self.run(|inner| inner.serialize_newtype_struct(name, &value))
+        },
+        newtype_struct
+    );
+
+    impl_serialize!(
+        {
+            let value = self.nested(value);
+            self.within(Segment::Variant(variant), |inner| {
+                inner.serialize_newtype_variant(name, variant_index, variant, &value)
+            })
+        },
+        newtype_variant
+    );
+
+    impl_serialize!(self.start(None, |inner| inner.serialize_seq(len)), seq);
+    impl_serialize!(self.start(None, |inner| inner.serialize_tuple(len)), tuple);
+    impl_serialize!(self.start(None, |inner| inner.serialize_tuple_struct(name, len)), tuple_struct);
+    impl_serialize!(
+        self.start(Some(variant), |inner| inner.serialize_tuple_variant(name, variant_index, variant, len)),
+        tuple_variant
+    );
+    impl_serialize!(self.start(None, |inner| inner.serialize_map(len)), map);
+    impl_serialize!(self.start(None, |inner| inner.serialize_struct(name, len)), struct);
+    impl_serialize!(
+        self.start(Some(variant), |inner| inner.serialize_struct_variant(name, variant_index, variant, len)),
+        struct_variant
+    );
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+fn forward<S: Serializer>(serializer: S, value: ValueRef<'_>) -> Result<S::Ok, S::Error> {
+    match value {
+        ValueRef::Bool(v) => serializer.serialize_bool(v),
+        ValueRef::I8(v) => serializer.serialize_i8(v),
+        ValueRef::I16(v) => serializer.serialize_i16(v),
+        ValueRef::I32(v) => serializer.serialize_i32(v),
+        ValueRef::I64(v) => serializer.serialize_i64(v),
+        ValueRef::I128(v) => serializer.serialize_i128(v),
+        ValueRef::U8(v) => serializer.serialize_u8(v),
+        ValueRef::U16(v) => serializer.serialize_u16(v),
+        ValueRef::U32(v) => serializer.serialize_u32(v),
+        ValueRef::U64(v) => serializer.serialize_u64(v),
+        ValueRef::U128(v) => serializer.serialize_u128(v),
+        ValueRef::F32(v) => serializer.serialize_f32(v),
+        ValueRef::F64(v) => serializer.serialize_f64(v),
+        ValueRef::Char(v) => serializer.serialize_char(v),
+        ValueRef::Str(v) => serializer.serialize_str(v),
+        ValueRef::Bytes(v) => serializer.serialize_bytes(v),
+        ValueRef::None => serializer.serialize_none(),
+        ValueRef::Unit => serializer.serialize_unit(),
+        ValueRef::UnitStruct { name } => serializer.serialize_unit_struct(name),
+        ValueRef::UnitVariant { name, variant_index, variant } => {
+            serializer.serialize_unit_variant(name, variant_index, variant)
+        }
+        value => unreachable!("{} isn't forwarded by value", value.kind()),
+    }
+}
+
+/// Value serialized by the inner serializer with [WithPath] around the nested serializer.
+struct Tracked<'a, 'b, T: ?Sized> {
+    value: &'b T,
+    track: &'a Track,
+    key: bool,
+}
+
+/// Errors of the nested serializer are unwrapped, the outer [WithPath] annotates them again.
+impl<'a, 'b, T: ?Sized + Serialize> Serialize for Tracked<'a, 'b, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let serializer = WithPath {
+            inner: serializer,
+            track: self.track,
+            key: self.key,
+        };
+
+        match self.value.serialize(serializer) {
+            Ok(ok) => Ok(ok),
+            Err(PathError { path, error }) => {
+                //custom errors of the value have the root path, the value itself failed
+                if path.0.is_empty() {
+                    self.track.fail();
+                }
+
+                Err(error)
+            }
+        }
+    }
+}
+
+/// Compound value of the inner serializer, returned by `serialize_seq`, `serialize_map`, etc. of [WithPath].
+pub struct WithPathCompound<'a, C> {
+    inner: C,
+    track: &'a Track,
+    variant: Option<&'static str>,
+    index: usize,
+    key: Option<Segment>,
+}
+
+impl<'a, C> WithPathCompound<'a, C> {
+    fn element<T: ?Sized, E>(
+        &mut self,
+        value: &T,
+        f: impl FnOnce(&mut C, &Tracked<'a, '_, T>) -> Result<(), E>,
+    ) -> Result<(), PathError<E>> {
+        let segment = Segment::Index(self.index);
+        self.index += 1;
+        self.nested(value, segment, false, f).0
+    }
+
+    //returns the segment back, a key names it while it is serialized
+    fn nested<T: ?Sized, E>(
+        &mut self,
+        value: &T,
+        segment: Segment,
+        key: bool,
+        f: impl FnOnce(&mut C, &Tracked<'a, '_, T>) -> Result<(), E>,
+    ) -> (Result<(), PathError<E>>, Option<Segment>) {
+        let value = Tracked {
+            value,
+            track: self.track,
+            key,
+        };
+
+        let (inner, track) = (&mut self.inner, self.track);
+        let mut named = None;
+
+        let result = Self::within(track, self.variant, || {
+            track.push(segment);
+            let result = track.call(|| f(inner, &value));
+            named = track.pop();
+            result
+        });
+
+        (result, named)
+    }
+
+    fn call<T, E>(&mut self, f: impl FnOnce(&mut C) -> Result<T, E>) -> Result<T, PathError<E>> {
+        let (inner, track) = (&mut self.inner, self.track);
+
+        Self::within(track, self.variant, || track.call(|| f(inner)))
+    }
+
+    fn end<T, E>(self, f: impl FnOnce(C) -> Result<T, E>) -> Result<T, PathError<E>> {
+        let (inner, track) = (self.inner, self.track);
+
+        Self::within(track, self.variant, || track.call(|| f(inner)))
+    }
+
+    //segments of the value are below the variant of the compound
+    fn within<T, E>(track: &Track, variant: Option<&'static str>, f: impl FnOnce() -> Result<T, E>) -> Result<T, PathError<E>> {
+        if let Some(variant) = variant {
+            track.push(Segment::Variant(variant));
+        }
+
+        let result = f().map_err(|error| track.annotate(error));
+
+        if variant.is_some() {
+            track.pop();
+        }
+        result
+    }
+}
+
+impl<'a, C: ser::SerializeSeq> ser::SerializeSeq for WithPathCompound<'a, C> {
+    type Ok = C::Ok;
+    type Error = PathError<C::Error>;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), PathError<C::Error>> {
+        self.element(value, |inner, value| inner.serialize_element(value))
+    }
+
+    fn end(self) -> Result<C::Ok, PathError<C::Error>> {
+        WithPathCompound::end(self, C::end)
+    }
+}
+
+impl<'a, C: ser::SerializeTuple> ser::SerializeTuple for WithPathCompound<'a, C> {
+    type Ok = C::Ok;
+    type Error = PathError<C::Error>;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), PathError<C::Error>> {
+        self.element(value, |inner, value| inner.serialize_element(value))
+    }
+
+    fn end(self) -> Result<C::Ok, PathError<C::Error>> {
+        WithPathCompound::end(self, C::end)
+    }
+}
+
+impl<'a, C: ser::SerializeTupleStruct> ser::SerializeTupleStruct for WithPathCompound<'a, C> {
+    type Ok = C::Ok;
+    type Error = PathError<C::Error>;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), PathError<C::Error>> {
+        self.element(value, |inner, value| inner.serialize_field(value))
+    }
+
+    fn end(self) -> Result<C::Ok, PathError<C::Error>> {
+        WithPathCompound::end(self, C::end)
+    }
+}
+
+impl<'a, C: ser::SerializeTupleVariant> ser::SerializeTupleVariant for WithPathCompound<'a, C> {
+    type Ok = C::Ok;
+    type Error = PathError<C::Error>;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), PathError<C::Error>> {
+        self.element(value, |inner, value| inner.serialize_field(value))
+    }
+
+    fn end(self) -> Result<C::Ok, PathError<C::Error>> {
+        WithPathCompound::end(self, C::end)
+    }
+}
+
+impl<'a, C: ser::SerializeMap> ser::SerializeMap for WithPathCompound<'a, C> {
+    type Ok = C::Ok;
+    type Error = PathError<C::Error>;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), PathError<C::Error>> {
+        let (result, segment) = self.nested(key, Segment::UnknownKey, true, |inner, key| inner.serialize_key(key));
+
+        self.key = segment;
+        result
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), PathError<C::Error>> {
+        let segment = self.key.take().unwrap_or(Segment::UnknownKey);
+
+        self.nested(value, segment, false, |inner, value| inner.serialize_value(value)).0
+    }
+
+    fn end(self) -> Result<C::Ok, PathError<C::Error>> {
+        WithPathCompound::end(self, C::end)
+    }
+}
+
+impl<'a, C: ser::SerializeStruct> ser::SerializeStruct for WithPathCompound<'a, C> {
+    type Ok = C::Ok;
+    type Error = PathError<C::Error>;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), PathError<C::Error>> {
+        self.nested(value, Segment::Field(key), false, |inner, value| inner.serialize_field(key, value)).0
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), PathError<C::Error>> {
+        self.call(|inner| inner.skip_field(key))
+    }
+
+    fn end(self) -> Result<C::Ok, PathError<C::Error>> {
+        WithPathCompound::end(self, C::end)
+    }
+}
+
+impl<'a, C: ser::SerializeStructVariant> ser::SerializeStructVariant for WithPathCompound<'a, C> {
+    type Ok = C::Ok;
+    type Error = PathError<C::Error>;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), PathError<C::Error>> {
+        self.nested(value, Segment::Field(key), false, |inner, value| inner.serialize_field(key, value)).0
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), PathError<C::Error>> {
+        self.call(|inner| inner.skip_field(key))
+    }
+
+    fn end(self) -> Result<C::Ok, PathError<C::Error>> {
+        WithPathCompound::end(self, C::end)
+    }
+}
+
+/// Names primitive map keys for [`Segment::Key`].
+fn key_name(key: ValueRef<'_>) -> Option<String> {
+    match key {
+        ValueRef::Bool(v) => Some(v.to_string()),
+        ValueRef::I8(v) => Some(v.to_string()),
+        ValueRef::I16(v) => Some(v.to_string()),
+        ValueRef::I32(v) => Some(v.to_string()),
+        ValueRef::I64(v) => Some(v.to_string()),
+        ValueRef::I128(v) => Some(v.to_string()),
+        ValueRef::U8(v) => Some(v.to_string()),
+        ValueRef::U16(v) => Some(v.to_string()),
+        ValueRef::U32(v) => Some(v.to_string()),
+        ValueRef::U64(v) => Some(v.to_string()),
+        ValueRef::U128(v) => Some(v.to_string()),
+        ValueRef::Char(v) => Some(alloc::format!("{:?}", v)),
+        ValueRef::Str(v) => Some(alloc::format!("{:?}", v)),
+        ValueRef::UnitVariant { variant, .. } => Some(variant.to_string()),
+        _ => None,
+    }
+}
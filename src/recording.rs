@@ -0,0 +1,584 @@
+use crate as impl_serialize;
+use crate::{ValueError, ValueKind, ValueRef};
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+use serde::ser::{self, Serialize};
+
+/// One call of a serialize method, recorded by [RecordingSerializer].
+///
+/// Compound values are recorded as start, elements and end. Struct fields are preceded by [`Event::Field`]
+/// or recorded as [`Event::SkipField`], map keys are preceded by [`Event::Key`] and followed by the value.
+#[derive(Clone, Debug)]
+pub enum Event {
+    /// `serialize_bool`
+    Bool(bool),
+    /// `serialize_i8`
+    I8(i8),
+    /// `serialize_i16`
+    I16(i16),
+    /// `serialize_i32`
+    I32(i32),
+    /// `serialize_i64`
+    I64(i64),
+    /// `serialize_i128`
+    I128(i128),
+    /// `serialize_u8`
+    U8(u8),
+    /// `serialize_u16`
+    U16(u16),
+    /// `serialize_u32`
+    U32(u32),
+    /// `serialize_u64`
+    U64(u64),
+    /// `serialize_u128`
+    U128(u128),
+    /// `serialize_f32`
+    F32(f32),
+    /// `serialize_f64`
+    F64(f64),
+    /// `serialize_char`
+    Char(char),
+    /// `serialize_str`
+    Str(String),
+    /// `serialize_bytes`
+    Bytes(Vec<u8>),
+    /// `serialize_none`
+    None,
+    /// `serialize_some`, followed by the value
+    Some,
+    /// `serialize_unit`
+    Unit,
+    /// `serialize_unit_struct`
+    UnitStruct {
+        name: &'static str,
+    },
+    /// `serialize_unit_variant`
+    UnitVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    },
+    /// `serialize_newtype_struct`, followed by the value
+    NewtypeStruct {
+        name: &'static str,
+    },
+    /// `serialize_newtype_variant`, followed by the value
+    NewtypeVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    },
+    /// `serialize_seq`
+    SeqStart {
+        len: Option<usize>,
+    },
+    /// `SerializeSeq::end`
+    SeqEnd,
+    /// `serialize_tuple`
+    TupleStart {
+        len: usize,
+    },
+    /// `SerializeTuple::end`
+    TupleEnd,
+    /// `serialize_tuple_struct`
+    TupleStructStart {
+        name: &'static str,
+        len: usize,
+    },
+    /// `SerializeTupleStruct::end`
+    TupleStructEnd,
+    /// `serialize_tuple_variant`
+    TupleVariantStart {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    },
+    /// `SerializeTupleVariant::end`
+    TupleVariantEnd,
+    /// `serialize_map`
+    MapStart {
+        len: Option<usize>,
+    },
+    /// `SerializeMap::end`
+    MapEnd,
+    /// `serialize_struct`
+    StructStart {
+        name: &'static str,
+        len: usize,
+    },
+    /// `SerializeStruct::end`
+    StructEnd,
+    /// `serialize_struct_variant`
+    StructVariantStart {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    },
+    /// `SerializeStructVariant::end`
+    StructVariantEnd,
+    /// Key of the following struct or struct variant field
+    Field(&'static str),
+    /// `skip_field` of a struct or struct variant
+    SkipField(&'static str),
+    /// `SerializeMap::serialize_key`, followed by the key
+    Key,
+}
+
+/// Compares floats with `total_cmp`, as [`diff`](crate::diff) does, so `NaN` equals itself and `0.0`
+/// differs from `-0.0`.
+impl PartialEq for Event {
+    fn eq(&self, other: &Event) -> bool {
+        match (self, other) {
+            (Event::Bool(left), Event::Bool(right)) => left == right,
+            (Event::I8(left), Event::I8(right)) => left == right,
+            (Event::I16(left), Event::I16(right)) => left == right,
+            (Event::I32(left), Event::I32(right)) => left == right,
+            (Event::I64(left), Event::I64(right)) => left == right,
+            (Event::I128(left), Event::I128(right)) => left == right,
+            (Event::U8(left), Event::U8(right)) => left == right,
+            (Event::U16(left), Event::U16(right)) => left == right,
+            (Event::U32(left), Event::U32(right)) => left == right,
+            (Event::U64(left), Event::U64(right)) => left == right,
+            (Event::U128(left), Event::U128(right)) => left == right,
+            (Event::F32(left), Event::F32(right)) => left.total_cmp(right).is_eq(),
+            (Event::F64(left), Event::F64(right)) => left.total_cmp(right).is_eq(),
+            (Event::Char(left), Event::Char(right)) => left == right,
+            (Event::Str(left), Event::Str(right)) => left == right,
+            (Event::Bytes(left), Event::Bytes(right)) => left == right,
+            (Event::UnitStruct { name }, Event::UnitStruct { name: right_name })
+            | (Event::NewtypeStruct { name }, Event::NewtypeStruct { name: right_name }) => name == right_name,
+            (
+                Event::UnitVariant { name, variant_index, variant },
+                Event::UnitVariant { name: right_name, variant_index: right_index, variant: right_variant },
+            )
+            | (
+                Event::NewtypeVariant { name, variant_index, variant },
+                Event::NewtypeVariant { name: right_name, variant_index: right_index, variant: right_variant },
+            ) => (name, variant_index, variant) == (right_name, right_index, right_variant),
+            (Event::SeqStart { len }, Event::SeqStart { len: right_len })
+            | (Event::MapStart { len }, Event::MapStart { len: right_len }) => len == right_len,
+            (Event::TupleStart { len }, Event::TupleStart { len: right_len }) => len == right_len,
+            (Event::TupleStructStart { name, len }, Event::TupleStructStart { name: right_name, len: right_len })
+            | (Event::StructStart { name, len }, Event::StructStart { name: right_name, len: right_len }) => {
+                (name, len) == (right_name, right_len)
+            }
+            (
+                Event::TupleVariantStart { name, variant_index, variant, len },
+                Event::TupleVariantStart {
+                    name: right_name,
+                    variant_index: right_index,
+                    variant: right_variant,
+                    len: right_len,
+                },
+            )
+            | (
+                Event::StructVariantStart { name, variant_index, variant, len },
+                Event::StructVariantStart {
+                    name: right_name,
+                    variant_index: right_index,
+                    variant: right_variant,
+                    len: right_len,
+                },
+            ) => (name, variant_index, variant, len) == (right_name, right_index, right_variant, right_len),
+            (Event::Field(left), Event::Field(right)) | (Event::SkipField(left), Event::SkipField(right)) => {
+                left == right
+            }
+            (Event::None, Event::None)
+            | (Event::Some, Event::Some)
+            | (Event::Unit, Event::Unit)
+            | (Event::SeqEnd, Event::SeqEnd)
+            | (Event::TupleEnd, Event::TupleEnd)
+            | (Event::TupleStructEnd, Event::TupleStructEnd)
+            | (Event::TupleVariantEnd, Event::TupleVariantEnd)
+            | (Event::MapEnd, Event::MapEnd)
+            | (Event::StructEnd, Event::StructEnd)
+            | (Event::StructVariantEnd, Event::StructVariantEnd)
+            | (Event::Key, Event::Key) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Event {
+    fn start(value: ValueRef<'_>) -> Event {
+        match value {
+            ValueRef::Bool(v) => Event::Bool(v),
+            ValueRef::I8(v) => Event::I8(v),
+            ValueRef::I16(v) => Event::I16(v),
+            ValueRef::I32(v) => Event::I32(v),
+            ValueRef::I64(v) => Event::I64(v),
+            ValueRef::I128(v) => Event::I128(v),
+            ValueRef::U8(v) => Event::U8(v),
+            ValueRef::U16(v) => Event::U16(v),
+            ValueRef::U32(v) => Event::U32(v),
+            ValueRef::U64(v) => Event::U64(v),
+            ValueRef::U128(v) => Event::U128(v),
+            ValueRef::F32(v) => Event::F32(v),
+            ValueRef::F64(v) => Event::F64(v),
+            ValueRef::Char(v) => Event::Char(v),
+            ValueRef::Str(v) => Event::Str(v.to_string()),
+            ValueRef::Bytes(v) => Event::Bytes(v.to_vec()),
+            ValueRef::None => Event::None,
+            ValueRef::Some(_) => Event::Some,
+            ValueRef::Unit => Event::Unit,
+            ValueRef::UnitStruct { name } => Event::UnitStruct { name },
+            ValueRef::UnitVariant { name, variant_index, variant } => Event::UnitVariant { name, variant_index, variant },
+            ValueRef::NewtypeStruct { name, .. } => Event::NewtypeStruct { name },
+            ValueRef::NewtypeVariant { name, variant_index, variant, .. } => {
+                Event::NewtypeVariant { name, variant_index, variant }
+            }
+            ValueRef::Seq { len } => Event::SeqStart { len },
+            ValueRef::Tuple { len } => Event::TupleStart { len },
+            ValueRef::TupleStruct { name, len } => Event::TupleStructStart { name, len },
+            ValueRef::TupleVariant { name, variant_index, variant, len } => {
+                Event::TupleVariantStart { name, variant_index, variant, len }
+            }
+            ValueRef::Map { len } => Event::MapStart { len },
+            ValueRef::Struct { name, len } => Event::StructStart { name, len },
+            ValueRef::StructVariant { name, variant_index, variant, len } => {
+                Event::StructVariantStart { name, variant_index, variant, len }
+            }
+        }
+    }
+
+    fn end(kind: ValueKind) -> Event {
+        match kind {
+            ValueKind::Seq => Event::SeqEnd,
+            ValueKind::Tuple => Event::TupleEnd,
+            ValueKind::TupleStruct => Event::TupleStructEnd,
+            ValueKind::TupleVariant => Event::TupleVariantEnd,
+            ValueKind::Map => Event::MapEnd,
+            ValueKind::Struct => Event::StructEnd,
+            ValueKind::StructVariant => Event::StructVariantEnd,
+            kind => unreachable!("{} isn't compound", kind),
+        }
+    }
+}
+
+/// Serializes `value` into flat list of [Event]s.
+pub fn to_events<T: ?Sized + Serialize>(value: &T) -> Result<Vec<Event>, ValueError> {
+    let mut events = Vec::new();
+
+    value.serialize(RecordingSerializer::new(&mut events))?;
+    Ok(events)
+}
+
+/// Serializer what appends an [Event] per call to a list.
+/// # Example
+#[doc = include_str!("../docs/recording.md")]
+#[derive(Debug)]
+pub struct RecordingSerializer<'a> {
+    events: &'a mut Vec<Event>,
+}
+
+impl<'a> RecordingSerializer<'a> {
+    /// Appends events to `events`.
+    pub fn new(events: &'a mut Vec<Event>) -> Self {
+        RecordingSerializer { events }
+    }
+
+    fn record(self, value: ValueRef<'_>) -> Self {
+        self.events.push(Event::start(value));
+        self
+    }
+}
+
+impl<'a> ser::Serializer for RecordingSerializer<'a> {
+    type Ok = ();
+    type Error = ValueError;
+
+    type SerializeMap = RecordingCompound<'a>;
+    type SerializeSeq = RecordingCompound<'a>;
+    type SerializeStruct = RecordingCompound<'a>;
+    type SerializeStructVariant = RecordingCompound<'a>;
+    type SerializeTuple = RecordingCompound<'a>;
+    type SerializeTupleStruct = RecordingCompound<'a>;
+    type SerializeTupleVariant = RecordingCompound<'a>;
+
+    impl_serialize!(value.serialize(self.record(value_ref)), [some, newtype_struct, newtype_variant]);
+
+    impl_serialize!(
+        value: {
+            self.record(value_ref);
+            Ok(())
+        },
+        compound: Ok(RecordingCompound {
+            end: Event::end(value_ref.kind()),
+            events: self.record(value_ref).events,
+        }),
+        [
+            bool,
+            i8, i16, i32, i64, i128,
+            u8, u16, u32, u64, u128,
+            f32, f64,
+            char, str, bytes,
+            none, unit,
+            unit_struct, unit_variant,
+            seq, tuple, tuple_struct, tuple_variant,
+            map, struct, struct_variant
+        ]
+    );
+}
+
+/// Compound value being recorded, returned by `serialize_seq`, `serialize_map`, etc. of [RecordingSerializer].
+#[derive(Debug)]
+pub struct RecordingCompound<'a> {
+    events: &'a mut Vec<Event>,
+    end: Event,
+}
+
+impl<'a> RecordingCompound<'a> {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        value.serialize(RecordingSerializer::new(self.events))
+    }
+
+    fn field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), ValueError> {
+        self.events.push(Event::Field(key));
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), ValueError> {
+        self.events.push(self.end);
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeSeq for RecordingCompound<'a> {
+    type Ok = ();
+    type Error = ValueError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), ValueError> {
+        RecordingCompound::end(self)
+    }
+}
+
+impl<'a> ser::SerializeTuple for RecordingCompound<'a> {
+    type Ok = ();
+    type Error = ValueError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), ValueError> {
+        RecordingCompound::end(self)
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for RecordingCompound<'a> {
+    type Ok = ();
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), ValueError> {
+        RecordingCompound::end(self)
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for RecordingCompound<'a> {
+    type Ok = ();
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), ValueError> {
+        RecordingCompound::end(self)
+    }
+}
+
+impl<'a> ser::SerializeMap for RecordingCompound<'a> {
+    type Ok = ();
+    type Error = ValueError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), ValueError> {
+        self.events.push(Event::Key);
+        self.element(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), ValueError> {
+        RecordingCompound::end(self)
+    }
+}
+
+impl<'a> ser::SerializeStruct for RecordingCompound<'a> {
+    type Ok = ();
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), ValueError> {
+        self.field(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), ValueError> {
+        self.events.push(Event::SkipField(key));
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), ValueError> {
+        RecordingCompound::end(self)
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for RecordingCompound<'a> {
+    type Ok = ();
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), ValueError> {
+        self.field(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), ValueError> {
+        self.events.push(Event::SkipField(key));
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), ValueError> {
+        RecordingCompound::end(self)
+    }
+}
+
+/// Asserts that serializing a value records the expected [Event]s,
+/// panicking with a line diff of the event lists otherwise.
+/// # Example
+/// ```
+/// use impl_serialize::{assert_events, Event};
+///
+/// assert_events!(Some((1u8, "a")), [
+///     Event::Some,
+///     Event::TupleStart { len: 2 },
+///     Event::U8(1),
+///     Event::Str("a".to_string()),
+///     Event::TupleEnd,
+/// ]);
+/// ```
+#[macro_export]
+macro_rules! assert_events {
+    ($value:expr, [$($event:expr),* $(,)?]) => {
+        $crate::check_events(&$value, &[$($event),*])
+    };
+}
+
+/// Panics if serializing `value` fails or records events other than `expected`. Used by [`assert_events!`].
+#[track_caller]
+pub fn check_events<T: ?Sized + Serialize>(value: &T, expected: &[Event]) {
+    let found = match to_events(value) {
+        Ok(found) => found,
+        Err(error) => panic!("serialization failed: {}", error),
+    };
+
+    if found != expected {
+        panic!(
+            "events differ (- expected, + found):\n{}",
+            EventsDiff {
+                expected,
+                found: &found,
+            }
+        );
+    }
+}
+
+struct EventsDiff<'a> {
+    expected: &'a [Event],
+    found: &'a [Event],
+}
+
+/// Largest table of the line diff, bigger differences report only the first mismatch.
+const MAX_DIFF_CELLS: usize = 1 << 20;
+
+impl<'a> fmt::Display for EventsDiff<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (expected, found) = (self.expected, self.found);
+
+        let prefix = expected.iter().zip(found).take_while(|(expected, found)| expected == found).count();
+        let suffix = expected[prefix..]
+            .iter()
+            .rev()
+            .zip(found[prefix..].iter().rev())
+            .take_while(|(expected, found)| expected == found)
+            .count();
+
+        for event in &expected[..prefix] {
+            writeln!(f, "      {:?}", event)?;
+        }
+
+        let middle = (&expected[prefix..expected.len() - suffix], &found[prefix..found.len() - suffix]);
+
+        if (middle.0.len() + 1).saturating_mul(middle.1.len() + 1) <= MAX_DIFF_CELLS {
+            write_lines(f, middle.0, middle.1)?;
+        } else {
+            let (expected, found) = (middle.0.split_first(), middle.1.split_first());
+
+            if let Some((event, _)) = expected {
+                writeln!(f, "    - {:?}", event)?;
+            }
+            if let Some((event, _)) = found {
+                writeln!(f, "    + {:?}", event)?;
+            }
+
+            writeln!(
+                f,
+                "    ... {} more expected and {} more found events differ, too many to diff",
+                expected.map_or(0, |(_, rest)| rest.len()),
+                found.map_or(0, |(_, rest)| rest.len())
+            )?;
+        }
+
+        for event in &expected[expected.len() - suffix..] {
+            writeln!(f, "      {:?}", event)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a line diff of events, which have no common prefix or suffix.
+fn write_lines(f: &mut fmt::Formatter, expected: &[Event], found: &[Event]) -> fmt::Result {
+    //common[i][j] is length of the longest common subsequence of expected[i..] and found[j..]
+    let mut common = vec![vec![0usize; found.len() + 1]; expected.len() + 1];
+
+    for i in (0..expected.len()).rev() {
+        for j in (0..found.len()).rev() {
+            common[i][j] = if expected[i] == found[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+
+    while i < expected.len() || j < found.len() {
+        if i < expected.len() && j < found.len() && expected[i] == found[j] {
+            writeln!(f, "      {:?}", expected[i])?;
+            i += 1;
+            j += 1;
+        } else if j == found.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1]) {
+            writeln!(f, "    - {:?}", expected[i])?;
+            i += 1;
+        } else {
+            writeln!(f, "    + {:?}", found[j])?;
+            j += 1;
+        }
+    }
+
+    Ok(())
+}
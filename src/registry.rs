@@ -0,0 +1,73 @@
+use crate::ValueKind;
+
+/// Methods what `impl_serialize!` generated for a serializer.
+///
+/// Implemented by the registry form of `impl_serialize!`, which wraps the whole
+/// `impl Serializer` block:
+/// ```
+/// use impl_serialize::{impl_serialize, Registry, ValueKind};
+/// use serde::ser;
+///
+/// struct IntSerializer;
+///
+/// impl_serialize! {
+///     impl ser::Serializer for IntSerializer {
+///         type Ok = i64;
+///         type Error = serde::de::value::Error;
+///
+///         type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+///         type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+///         type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+///         type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+///         type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+///         type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+///         type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+///
+///         impl_serialize!(Ok(v as i64), [i8, i16, i32, i64]);
+///
+///         impl_serialize!(Err(ser::Error::custom(value_type)), [
+///             bool,
+///             bytes,
+///             i128,
+///             u8, u16, u32, u64, u128,
+///             f32, f64,
+///             char,
+///             str,
+///             none, some, unit,
+///             unit_struct, unit_variant,
+///             newtype_struct, newtype_variant,
+///             seq, map,
+///             tuple, tuple_struct, tuple_variant,
+///             struct, struct_variant
+///         ]);
+///     }
+/// }
+///
+/// assert_eq!(IntSerializer::IMPLEMENTED_BY_MACRO.len(), 2);
+/// assert_eq!(IntSerializer::macro_label(ValueKind::I32), Some("Ok(v as i64)"));
+/// assert_eq!(IntSerializer::macro_label(ValueKind::Str), Some("Err(ser::Error::custom(value_type))"));
+/// ```
+/// The block may be generic and have a `where` clause. Associated types, `impl_serialize!`
+/// invocations and hand-written methods go in any order, every invocation is registered.
+/// Anything else in the block is a compile error.
+pub trait Registry {
+    /// One registration per `impl_serialize!` invocation, in order.
+    const IMPLEMENTED_BY_MACRO: &'static [Registration];
+
+    /// Returns label of the invocation what generated method of `kind`.
+    fn macro_label(kind: ValueKind) -> Option<&'static str> {
+        Self::IMPLEMENTED_BY_MACRO
+            .iter()
+            .find(|registration| registration.kinds.contains(&kind))
+            .map(|registration| registration.label)
+    }
+}
+
+/// Methods generated by one `impl_serialize!` invocation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Registration {
+    /// Expression of the invocation as written.
+    pub label: &'static str,
+    /// Kinds of the generated methods.
+    pub kinds: &'static [ValueKind],
+}
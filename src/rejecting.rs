@@ -0,0 +1,154 @@
+use crate::ValueKind;
+use serde::ser::{self, Serialize};
+
+/// Compound serializer what rejects every element, key and field.
+///
+/// Unlike [`ser::Impossible`], it can be constructed, so a serializer can start a compound
+/// value and fail on its first element. The error is built from `value_type` of the element,
+/// or from `value_type` of the compound itself when [`end`](ser::SerializeSeq::end) is called
+/// without elements.
+/// # Example
+#[doc = include_str!("../docs/rejecting.md")]
+pub struct Rejecting<Ok, Error> {
+    value_type: &'static str,
+    end: Option<Ok>,
+    reject: fn(&'static str) -> Error,
+}
+
+impl<Ok, Error: ser::Error> Rejecting<Ok, Error> {
+    /// Rejects with `Error::custom("cannot serialize {value_type}")`.
+    pub fn new(value_type: &'static str) -> Self {
+        Self::with_error(value_type, custom)
+    }
+}
+
+impl<Ok, Error> Rejecting<Ok, Error> {
+    /// Rejects with error returned by `reject` for `value_type` of the element.
+    pub fn with_error(value_type: &'static str, reject: fn(&'static str) -> Error) -> Self {
+        Rejecting {
+            value_type,
+            end: None,
+            reject,
+        }
+    }
+
+    /// Accepts compound without elements, returning `ok` from `end`.
+    pub fn end_with(self, ok: Ok) -> Self {
+        Rejecting {
+            end: Some(ok),
+            ..self
+        }
+    }
+
+    fn reject<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let value_type = ValueKind::of(value).map_or("custom", ValueKind::as_str);
+
+        Err((self.reject)(value_type))
+    }
+
+    fn end(self) -> Result<Ok, Error> {
+        match self.end {
+            Some(ok) => Ok(ok),
+            None => Err((self.reject)(self.value_type)),
+        }
+    }
+}
+
+fn custom<Error: ser::Error>(value_type: &'static str) -> Error {
+    Error::custom(format_args!("cannot serialize {}", value_type))
+}
+
+impl<Ok, Error: ser::Error> ser::SerializeSeq for Rejecting<Ok, Error> {
+    type Ok = Ok;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.reject(value)
+    }
+
+    fn end(self) -> Result<Ok, Error> {
+        Rejecting::end(self)
+    }
+}
+
+impl<Ok, Error: ser::Error> ser::SerializeTuple for Rejecting<Ok, Error> {
+    type Ok = Ok;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.reject(value)
+    }
+
+    fn end(self) -> Result<Ok, Error> {
+        Rejecting::end(self)
+    }
+}
+
+impl<Ok, Error: ser::Error> ser::SerializeTupleStruct for Rejecting<Ok, Error> {
+    type Ok = Ok;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.reject(value)
+    }
+
+    fn end(self) -> Result<Ok, Error> {
+        Rejecting::end(self)
+    }
+}
+
+impl<Ok, Error: ser::Error> ser::SerializeTupleVariant for Rejecting<Ok, Error> {
+    type Ok = Ok;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.reject(value)
+    }
+
+    fn end(self) -> Result<Ok, Error> {
+        Rejecting::end(self)
+    }
+}
+
+impl<Ok, Error: ser::Error> ser::SerializeMap for Rejecting<Ok, Error> {
+    type Ok = Ok;
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.reject(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.reject(value)
+    }
+
+    fn end(self) -> Result<Ok, Error> {
+        Rejecting::end(self)
+    }
+}
+
+impl<Ok, Error: ser::Error> ser::SerializeStruct for Rejecting<Ok, Error> {
+    type Ok = Ok;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, _key: &'static str, value: &T) -> Result<(), Error> {
+        self.reject(value)
+    }
+
+    fn end(self) -> Result<Ok, Error> {
+        Rejecting::end(self)
+    }
+}
+
+impl<Ok, Error: ser::Error> ser::SerializeStructVariant for Rejecting<Ok, Error> {
+    type Ok = Ok;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, _key: &'static str, value: &T) -> Result<(), Error> {
+        self.reject(value)
+    }
+
+    fn end(self) -> Result<Ok, Error> {
+        Rejecting::end(self)
+    }
+}
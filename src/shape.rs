@@ -0,0 +1,530 @@
+use crate as impl_serialize;
+use crate::{ValueError, ValueKind, ValueRef};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::{fmt, mem};
+use serde::ser::{self, Serialize};
+
+/// Serializes `value` into [Shape].
+/// # Example
+#[doc = include_str!("../docs/shape.md")]
+pub fn to_shape<T: ?Sized + Serialize>(value: &T) -> Result<Shape, ValueError> {
+    value.serialize(ShapeSerializer)
+}
+
+/// Shape of serialized values: kinds, names, fields and variants without the values.
+///
+/// Built by [ShapeSerializer] from one sample, shapes of more samples are combined with [`merge`](Shape::merge).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    /// Nothing seen yet, like elements of an empty sequence.
+    Unknown,
+    /// `value_type` of a value without inner values: bool, integers, floats, char, str, bytes and unit.
+    Primitive(ValueKind),
+    /// `serialize_none` and `serialize_some`, which of them were seen.
+    Option {
+        /// `None` was seen.
+        none: bool,
+        /// Shape of `Some` values, `None` if no `Some` was seen.
+        some: Option<Box<Shape>>,
+    },
+    /// `serialize_unit_struct`
+    UnitStruct {
+        name: &'static str,
+    },
+    /// `serialize_newtype_struct`
+    NewtypeStruct {
+        name: &'static str,
+        value: Box<Shape>,
+    },
+    /// Sequence with shape of all elements merged.
+    Seq(Box<Shape>),
+    /// `serialize_tuple`
+    Tuple(Vec<Shape>),
+    /// `serialize_tuple_struct`
+    TupleStruct {
+        name: &'static str,
+        fields: Vec<Shape>,
+    },
+    /// Map with shapes of all keys and all values merged.
+    Map {
+        key: Box<Shape>,
+        value: Box<Shape>,
+    },
+    /// `serialize_struct`
+    Struct {
+        name: &'static str,
+        fields: Vec<Field>,
+    },
+    /// Variants of an enum what were seen.
+    Enum {
+        name: &'static str,
+        variants: Vec<Variant>,
+    },
+    /// Shapes what can't be merged, like different variants of an untagged enum.
+    OneOf(Vec<Shape>),
+}
+
+/// Field of [`Shape::Struct`] or of a struct variant.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: &'static str,
+    pub shape: Shape,
+    /// The field was skipped or missing in some samples.
+    pub optional: bool,
+}
+
+/// Variant of [`Shape::Enum`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variant {
+    pub name: &'static str,
+    /// `unit_variant`, `newtype_variant`, `tuple_variant` or `struct_variant`.
+    pub kind: ValueKind,
+    /// [`Shape::Primitive`] of unit for unit variants, shape of the value for newtype variants,
+    /// [`Shape::Tuple`] for tuple variants and [`Shape::Struct`] named by the variant for struct variants.
+    pub shape: Shape,
+}
+
+impl Shape {
+    /// Combines shape of another sample into this one.
+    ///
+    /// Shapes of the same kind and name are merged recursively, fields missing in one of them become
+    /// optional and variants are collected. Other shapes become [`Shape::OneOf`].
+    pub fn merge(&mut self, other: Shape) {
+        if !matches!(self, Shape::OneOf(_)) {
+            if self.compatible(&other) {
+                return self.merge_compatible(other);
+            }
+
+            *self = Shape::OneOf(vec![mem::replace(self, Shape::Unknown)]);
+        }
+
+        if let Shape::OneOf(shapes) = self {
+            match other {
+                Shape::OneOf(others) => others.into_iter().for_each(|other| add(shapes, other)),
+                other => add(shapes, other),
+            }
+        }
+    }
+
+    fn compatible(&self, other: &Shape) -> bool {
+        match (self, other) {
+            (Shape::Unknown, _) | (_, Shape::Unknown) => true,
+            (Shape::Primitive(kind), Shape::Primitive(other)) => kind == other,
+            (Shape::Option { .. }, Shape::Option { .. })
+            | (Shape::Seq(_), Shape::Seq(_))
+            | (Shape::Map { .. }, Shape::Map { .. }) => true,
+            (Shape::UnitStruct { name }, Shape::UnitStruct { name: other })
+            | (Shape::NewtypeStruct { name, .. }, Shape::NewtypeStruct { name: other, .. })
+            | (Shape::Struct { name, .. }, Shape::Struct { name: other, .. })
+            | (Shape::Enum { name, .. }, Shape::Enum { name: other, .. }) => name == other,
+            (Shape::Tuple(fields), Shape::Tuple(other)) => fields.len() == other.len(),
+            (Shape::TupleStruct { name, fields }, Shape::TupleStruct { name: other_name, fields: other }) => {
+                name == other_name && fields.len() == other.len()
+            }
+            _ => false,
+        }
+    }
+
+    fn merge_compatible(&mut self, other: Shape) {
+        match (self, other) {
+            (_, Shape::Unknown) => {}
+            (this @ Shape::Unknown, other) => *this = other,
+            (Shape::Option { none, some }, Shape::Option { none: other_none, some: other_some }) => {
+                *none |= other_none;
+
+                match (some, other_some) {
+                    (Some(some), Some(other)) => some.merge(*other),
+                    (some @ None, other) => *some = other,
+                    (Some(_), None) => {}
+                }
+            }
+            (Shape::NewtypeStruct { value, .. }, Shape::NewtypeStruct { value: other, .. })
+            | (Shape::Seq(value), Shape::Seq(other)) => value.merge(*other),
+            (Shape::Tuple(fields), Shape::Tuple(other))
+            | (Shape::TupleStruct { fields, .. }, Shape::TupleStruct { fields: other, .. }) => {
+                for (field, other) in fields.iter_mut().zip(other) {
+                    field.merge(other);
+                }
+            }
+            (Shape::Map { key, value }, Shape::Map { key: other_key, value: other_value }) => {
+                key.merge(*other_key);
+                value.merge(*other_value);
+            }
+            (Shape::Struct { fields, .. }, Shape::Struct { fields: other, .. }) => merge_fields(fields, other),
+            (Shape::Enum { variants, .. }, Shape::Enum { variants: other, .. }) => {
+                for other in other {
+                    match variants
+                        .iter_mut()
+                        .find(|variant| variant.name == other.name && variant.kind == other.kind)
+                    {
+                        Some(variant) => variant.shape.merge(other.shape),
+                        None => variants.push(other),
+                    }
+                }
+            }
+            //primitives and unit structs are equal
+            _ => {}
+        }
+    }
+}
+
+fn add(shapes: &mut Vec<Shape>, other: Shape) {
+    match shapes.iter_mut().find(|shape| shape.compatible(&other)) {
+        Some(shape) => shape.merge(other),
+        None => shapes.push(other),
+    }
+}
+
+fn merge_fields(fields: &mut Vec<Field>, other: Vec<Field>) {
+    for field in fields.iter_mut() {
+        if !other.iter().any(|other| other.name == field.name) {
+            field.optional = true;
+        }
+    }
+
+    for other in other {
+        match fields.iter_mut().find(|field| field.name == other.name) {
+            Some(field) => {
+                field.shape.merge(other.shape);
+                field.optional |= other.optional;
+            }
+            None => fields.push(Field { optional: true, ..other }),
+        }
+    }
+}
+
+/// Rust-like notation, `Config { name: str, port?: Option<u16>, mode: Mode::Fast | Mode::Limited(u32) }`.
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Shape::Unknown => f.write_str("?"),
+            Shape::Primitive(ValueKind::Unit) => f.write_str("()"),
+            Shape::Primitive(kind) => f.write_str(kind.as_str()),
+            Shape::Option { some: Some(some), .. } => write!(f, "Option<{}>", some),
+            Shape::Option { some: None, .. } => f.write_str("Option<?>"),
+            Shape::UnitStruct { name } => f.write_str(name),
+            Shape::NewtypeStruct { name, value } => write!(f, "{}({})", name, value),
+            Shape::Seq(element) => write!(f, "[{}]", element),
+            Shape::Tuple(fields) => write_list(f, "(", fields, ", ", ")"),
+            Shape::TupleStruct { name, fields } => {
+                f.write_str(name)?;
+                write_list(f, "(", fields, ", ", ")")
+            }
+            Shape::Map { key, value } => write!(f, "{{{}: {}}}", key, value),
+            Shape::Struct { name, fields } => {
+                f.write_str(name)?;
+                write_fields(f, fields)
+            }
+            Shape::Enum { name, variants } => {
+                for (i, variant) in variants.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { " | " };
+                    write!(f, "{}{}::{}", separator, name, variant.name)?;
+
+                    match &variant.shape {
+                        _ if variant.kind == ValueKind::UnitVariant => {}
+                        Shape::Tuple(fields) if variant.kind == ValueKind::TupleVariant => {
+                            write_list(f, "(", fields, ", ", ")")?
+                        }
+                        Shape::Struct { fields, .. } if variant.kind == ValueKind::StructVariant => {
+                            write_fields(f, fields)?
+                        }
+                        shape => write!(f, "({})", shape)?,
+                    }
+                }
+
+                Ok(())
+            }
+            Shape::OneOf(shapes) => write_list(f, "", shapes, " | ", ""),
+        }
+    }
+}
+
+fn write_list(f: &mut fmt::Formatter, open: &str, shapes: &[Shape], separator: &str, close: &str) -> fmt::Result {
+    f.write_str(open)?;
+
+    for (i, shape) in shapes.iter().enumerate() {
+        if i > 0 {
+            f.write_str(separator)?;
+        }
+        write!(f, "{}", shape)?;
+    }
+
+    f.write_str(close)
+}
+
+fn write_fields(f: &mut fmt::Formatter, fields: &[Field]) -> fmt::Result {
+    if fields.is_empty() {
+        return f.write_str(" {}");
+    }
+
+    f.write_str(" { ")?;
+
+    for (i, field) in fields.iter().enumerate() {
+        let separator = if i == 0 { "" } else { ", " };
+        let optional = if field.optional { "?" } else { "" };
+        write!(f, "{}{}{}: {}", separator, field.name, optional, field.shape)?;
+    }
+
+    f.write_str(" }")
+}
+
+/// Serializer what returns [Shape] of the value.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ShapeSerializer;
+
+impl ShapeSerializer {
+    fn start(value: ValueRef<'_>) -> ShapeCompound {
+        let (shape, variant) = match value {
+            ValueRef::Seq { .. } => (Shape::Seq(Box::new(Shape::Unknown)), None),
+            ValueRef::Tuple { .. } => (Shape::Tuple(Vec::new()), None),
+            ValueRef::TupleStruct { name, .. } => (Shape::TupleStruct { name, fields: Vec::new() }, None),
+            ValueRef::TupleVariant { name, variant, .. } => {
+                (Shape::Tuple(Vec::new()), Some((name, variant, ValueKind::TupleVariant)))
+            }
+            ValueRef::Map { .. } => (
+                Shape::Map {
+                    key: Box::new(Shape::Unknown),
+                    value: Box::new(Shape::Unknown),
+                },
+                None,
+            ),
+            ValueRef::Struct { name, .. } => (Shape::Struct { name, fields: Vec::new() }, None),
+            ValueRef::StructVariant { name, variant, .. } => (
+                Shape::Struct {
+                    name: variant,
+                    fields: Vec::new(),
+                },
+                Some((name, variant, ValueKind::StructVariant)),
+            ),
+            value => unreachable!("{} isn't compound", value.kind()),
+        };
+
+        ShapeCompound { shape, variant }
+    }
+}
+
+fn enum_shape(name: &'static str, variant: &'static str, kind: ValueKind, shape: Shape) -> Shape {
+    Shape::Enum {
+        name,
+        variants: vec![Variant {
+            name: variant,
+            kind,
+            shape,
+        }],
+    }
+}
+
+impl ser::Serializer for ShapeSerializer {
+    type Ok = Shape;
+    type Error = ValueError;
+
+    type SerializeMap = ShapeCompound;
+    type SerializeSeq = ShapeCompound;
+    type SerializeStruct = ShapeCompound;
+    type SerializeStructVariant = ShapeCompound;
+    type SerializeTuple = ShapeCompound;
+    type SerializeTupleStruct = ShapeCompound;
+    type SerializeTupleVariant = ShapeCompound;
+
+    impl_serialize!(
+        Ok(Shape::Primitive(value_ref.kind())),
+        [
+            bool,
+            i8, i16, i32, i64, i128,
+            u8, u16, u32, u64, u128,
+            f32, f64,
+            char, str, bytes,
+            unit
+        ]
+    );
+
+    impl_serialize!(Ok(Shape::Option { none: true, some: None }), none);
+
+    impl_serialize!(
+        Ok(Shape::Option {
+            none: false,
+            some: Some(Box::new(value.serialize(self)?)),
+        }),
+        some
+    );
+
+    impl_serialize!(Ok(Shape::UnitStruct { name }), unit_struct);
+
+    impl_serialize!(
+        Ok(enum_shape(name, variant, ValueKind::UnitVariant, Shape::Primitive(ValueKind::Unit))),
+        unit_variant
+    );
+
+    impl_serialize!(
+        Ok(Shape::NewtypeStruct {
+            name,
+            value: Box::new(value.serialize(self)?),
+        }),
+        newtype_struct
+    );
+
+    impl_serialize!(
+        Ok(enum_shape(name, variant, ValueKind::NewtypeVariant, value.serialize(self)?)),
+        newtype_variant
+    );
+
+    impl_serialize!(Ok(ShapeSerializer::start(value_ref)), compounds);
+}
+
+/// Compound value of [ShapeSerializer].
+#[derive(Debug)]
+pub struct ShapeCompound {
+    shape: Shape,
+    //enum name, variant and kind of tuple and struct variants
+    variant: Option<(&'static str, &'static str, ValueKind)>,
+}
+
+impl ShapeCompound {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        let shape = value.serialize(ShapeSerializer)?;
+
+        match &mut self.shape {
+            Shape::Seq(element) => element.merge(shape),
+            Shape::Tuple(fields) | Shape::TupleStruct { fields, .. } => fields.push(shape),
+            shape => unreachable!("{} has no elements", shape),
+        }
+
+        Ok(())
+    }
+
+    fn field(&mut self, name: &'static str, shape: Shape, optional: bool) {
+        if let Shape::Struct { fields, .. } = &mut self.shape {
+            fields.push(Field { name, shape, optional });
+        }
+    }
+
+    fn entry<T: ?Sized + Serialize>(&mut self, value: &T, key: bool) -> Result<(), ValueError> {
+        let shape = value.serialize(ShapeSerializer)?;
+
+        if let Shape::Map { key: key_shape, value: value_shape } = &mut self.shape {
+            if key {
+                key_shape.merge(shape);
+            } else {
+                value_shape.merge(shape);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Shape, ValueError> {
+        Ok(match self.variant {
+            Some((name, variant, kind)) => enum_shape(name, variant, kind, self.shape),
+            None => self.shape,
+        })
+    }
+}
+
+impl ser::SerializeSeq for ShapeCompound {
+    type Ok = Shape;
+    type Error = ValueError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Shape, ValueError> {
+        ShapeCompound::end(self)
+    }
+}
+
+impl ser::SerializeTuple for ShapeCompound {
+    type Ok = Shape;
+    type Error = ValueError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Shape, ValueError> {
+        ShapeCompound::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for ShapeCompound {
+    type Ok = Shape;
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Shape, ValueError> {
+        ShapeCompound::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for ShapeCompound {
+    type Ok = Shape;
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Shape, ValueError> {
+        ShapeCompound::end(self)
+    }
+}
+
+impl ser::SerializeMap for ShapeCompound {
+    type Ok = Shape;
+    type Error = ValueError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), ValueError> {
+        self.entry(key, true)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.entry(value, false)
+    }
+
+    fn end(self) -> Result<Shape, ValueError> {
+        ShapeCompound::end(self)
+    }
+}
+
+impl ser::SerializeStruct for ShapeCompound {
+    type Ok = Shape;
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), ValueError> {
+        let shape = value.serialize(ShapeSerializer)?;
+        self.field(key, shape, false);
+        Ok(())
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), ValueError> {
+        self.field(key, Shape::Unknown, true);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Shape, ValueError> {
+        ShapeCompound::end(self)
+    }
+}
+
+impl ser::SerializeStructVariant for ShapeCompound {
+    type Ok = Shape;
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), ValueError> {
+        let shape = value.serialize(ShapeSerializer)?;
+        self.field(key, shape, false);
+        Ok(())
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), ValueError> {
+        self.field(key, Shape::Unknown, true);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Shape, ValueError> {
+        ShapeCompound::end(self)
+    }
+}
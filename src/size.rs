@@ -0,0 +1,408 @@
+use crate as impl_serialize;
+use crate::{ValueKind, ValueRef};
+use core::fmt::{self, Write};
+use serde::ser::{self, Serialize};
+
+/// Error of [SizeEstimator], only `Serialize` implementations can fail.
+pub type SizeError = serde::de::value::Error;
+
+/// Returns estimated size in bytes of `value` encoded by `model`.
+/// # Example
+#[doc = include_str!("../docs/size.md")]
+pub fn estimate_size<T: ?Sized + Serialize>(value: &T, model: &CostModel) -> Result<usize, SizeError> {
+    value.serialize(SizeEstimator::new(model))
+}
+
+/// Cost of one `ValueKind` in [CostModel].
+///
+/// Binary costs are added to the content of the value: bytes of strings, inner values, elements,
+/// entries and fields. The number they encode is the value of integers (zigzag for signed),
+/// code of chars, length of strings, bytes, sequences, tuples, maps and structs, index of enum variants,
+/// `0` for `none` and `1` for `some`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cost {
+    /// Constant number of bytes.
+    Fixed(usize),
+    /// LEB128 varint of the number, size of the type for floats.
+    Varint,
+    /// JSON text without whitespace and escapes, enum variants are externally tagged.
+    Text,
+}
+
+/// Cost of every `ValueKind` for [SizeEstimator].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostModel {
+    costs: [Cost; ValueKind::ALL.len()],
+}
+
+impl CostModel {
+    /// Every kind costs `cost`.
+    pub const fn uniform(cost: Cost) -> Self {
+        CostModel { costs: [cost; ValueKind::ALL.len()] }
+    }
+
+    /// Varints for integers, lengths and variant indexes, fixed floats, no prefix for values
+    /// with length known by the type, like postcard.
+    pub const fn compact_binary() -> Self {
+        CostModel::uniform(Cost::Varint)
+            .with(ValueKind::Bool, Cost::Fixed(1))
+            .with(ValueKind::I8, Cost::Fixed(1))
+            .with(ValueKind::U8, Cost::Fixed(1))
+            .with(ValueKind::Unit, Cost::Fixed(0))
+            .with(ValueKind::UnitStruct, Cost::Fixed(0))
+            .with(ValueKind::NewtypeStruct, Cost::Fixed(0))
+            .with(ValueKind::Tuple, Cost::Fixed(0))
+            .with(ValueKind::TupleStruct, Cost::Fixed(0))
+            .with(ValueKind::Struct, Cost::Fixed(0))
+    }
+
+    /// JSON text of every kind.
+    pub const fn json_ish() -> Self {
+        CostModel::uniform(Cost::Text)
+    }
+
+    /// Replaces cost of `kind`.
+    pub const fn with(mut self, kind: ValueKind, cost: Cost) -> Self {
+        self.costs[kind as usize] = cost;
+        self
+    }
+
+    /// Cost of `kind`.
+    pub const fn cost(&self, kind: ValueKind) -> Cost {
+        self.costs[kind as usize]
+    }
+}
+
+/// Serializer what returns estimated size of the value instead of encoding it.
+#[derive(Clone, Copy, Debug)]
+pub struct SizeEstimator<'a> {
+    model: &'a CostModel,
+}
+
+impl<'a> SizeEstimator<'a> {
+    /// Estimates by `model`.
+    pub fn new(model: &'a CostModel) -> Self {
+        SizeEstimator { model }
+    }
+
+    fn value(self, value: ValueRef<'_>) -> usize {
+        let kind = value.kind();
+        let cost = self.model.cost(kind);
+
+        if cost == Cost::Text {
+            return text_len(value);
+        }
+
+        let content = match value {
+            ValueRef::Str(v) => v.len(),
+            ValueRef::Bytes(v) => v.len(),
+            _ => 0,
+        };
+
+        let number = match value {
+            ValueRef::Bool(v) => v as u128,
+            ValueRef::I8(v) => zigzag(v.into()),
+            ValueRef::I16(v) => zigzag(v.into()),
+            ValueRef::I32(v) => zigzag(v.into()),
+            ValueRef::I64(v) => zigzag(v.into()),
+            ValueRef::I128(v) => zigzag(v),
+            ValueRef::U8(v) => v.into(),
+            ValueRef::U16(v) => v.into(),
+            ValueRef::U32(v) => v.into(),
+            ValueRef::U64(v) => v.into(),
+            ValueRef::U128(v) => v,
+            ValueRef::Char(v) => u32::from(v).into(),
+            ValueRef::Str(v) => v.len() as u128,
+            ValueRef::Bytes(v) => v.len() as u128,
+            ValueRef::UnitVariant { variant_index, .. } => variant_index.into(),
+            _ => 0,
+        };
+
+        let prefix = match (cost, value) {
+            (Cost::Varint, ValueRef::F32(_)) => 4,
+            (Cost::Varint, ValueRef::F64(_)) => 8,
+            _ => prefix(cost, number),
+        };
+
+        prefix + content
+    }
+
+    fn wrapped(self, value: ValueRef<'_>, inner: usize) -> usize {
+        let (number, variant) = match value {
+            ValueRef::Some(_) => (1, ""),
+            ValueRef::NewtypeVariant { variant_index, variant, .. } => (variant_index.into(), variant),
+            _ => (0, ""),
+        };
+
+        match self.model.cost(value.kind()) {
+            //{"variant":inner}
+            Cost::Text if value.kind() == ValueKind::NewtypeVariant => variant.len() + 5 + inner,
+            Cost::Text => inner,
+            cost => prefix(cost, number) + inner,
+        }
+    }
+
+    fn start(self, value: ValueRef<'_>) -> SizeCompound<'a> {
+        let (variant_index, variant) = match value {
+            ValueRef::TupleVariant { variant_index, variant, .. }
+            | ValueRef::StructVariant { variant_index, variant, .. } => (variant_index, variant),
+            _ => (0, ""),
+        };
+
+        SizeCompound {
+            estimator: self,
+            kind: value.kind(),
+            variant_index,
+            variant,
+            count: 0,
+            content: 0,
+        }
+    }
+}
+
+impl<'a> ser::Serializer for SizeEstimator<'a> {
+    type Ok = usize;
+    type Error = SizeError;
+
+    type SerializeMap = SizeCompound<'a>;
+    type SerializeSeq = SizeCompound<'a>;
+    type SerializeStruct = SizeCompound<'a>;
+    type SerializeStructVariant = SizeCompound<'a>;
+    type SerializeTuple = SizeCompound<'a>;
+    type SerializeTupleStruct = SizeCompound<'a>;
+    type SerializeTupleVariant = SizeCompound<'a>;
+
+    impl_serialize!(
+        Ok(self.value(value_ref)),
+        [
+            bool,
+            i8, i16, i32, i64, i128,
+            u8, u16, u32, u64, u128,
+            f32, f64,
+            char, str, bytes,
+            none, unit,
+            unit_struct, unit_variant
+        ]
+    );
+
+    impl_serialize!(
+        Ok(self.wrapped(value_ref, value.serialize(self)?)),
+        [some, newtype_struct, newtype_variant]
+    );
+
+    impl_serialize!(Ok(self.start(value_ref)), compounds);
+
+    //counts the text without collecting it, also without alloc
+    fn collect_str<T: ?Sized + fmt::Display>(self, value: &T) -> Result<usize, SizeError> {
+        let len = display_len(value);
+
+        Ok(match self.model.cost(ValueKind::Str) {
+            Cost::Text => len + 2,
+            cost => prefix(cost, len as u128) + len,
+        })
+    }
+}
+
+/// Compound value of [SizeEstimator], adds structure cost at the end.
+#[derive(Debug)]
+pub struct SizeCompound<'a> {
+    estimator: SizeEstimator<'a>,
+    kind: ValueKind,
+    variant_index: u32,
+    variant: &'static str,
+    count: usize,
+    content: usize,
+}
+
+impl<'a> SizeCompound<'a> {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SizeError> {
+        self.count += 1;
+        self.content += value.serialize(self.estimator)?;
+        Ok(())
+    }
+
+    fn field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), SizeError> {
+        if self.cost() == Cost::Text {
+            //"key":
+            self.content += key.len() + 3;
+        }
+
+        self.element(value)
+    }
+
+    fn cost(&self) -> Cost {
+        self.estimator.model.cost(self.kind)
+    }
+
+    fn end(self) -> Result<usize, SizeError> {
+        let commas = self.count.saturating_sub(1);
+
+        Ok(match (self.cost(), self.kind) {
+            //{"key":value,...}
+            (Cost::Text, ValueKind::Map) => 2 + self.content + self.count + commas,
+            //{"variant":[...]} and {"variant":{...}}
+            (Cost::Text, ValueKind::TupleVariant | ValueKind::StructVariant) => {
+                self.variant.len() + 5 + 2 + self.content + commas
+            }
+            (Cost::Text, _) => 2 + self.content + commas,
+            (cost, ValueKind::TupleVariant | ValueKind::StructVariant) => {
+                prefix(cost, self.variant_index.into()) + self.content
+            }
+            (cost, _) => prefix(cost, self.count as u128) + self.content,
+        })
+    }
+}
+
+impl<'a> ser::SerializeSeq for SizeCompound<'a> {
+    type Ok = usize;
+    type Error = SizeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SizeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<usize, SizeError> {
+        SizeCompound::end(self)
+    }
+}
+
+impl<'a> ser::SerializeTuple for SizeCompound<'a> {
+    type Ok = usize;
+    type Error = SizeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SizeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<usize, SizeError> {
+        SizeCompound::end(self)
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for SizeCompound<'a> {
+    type Ok = usize;
+    type Error = SizeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SizeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<usize, SizeError> {
+        SizeCompound::end(self)
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for SizeCompound<'a> {
+    type Ok = usize;
+    type Error = SizeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SizeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<usize, SizeError> {
+        SizeCompound::end(self)
+    }
+}
+
+impl<'a> ser::SerializeMap for SizeCompound<'a> {
+    type Ok = usize;
+    type Error = SizeError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), SizeError> {
+        self.element(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SizeError> {
+        self.content += value.serialize(self.estimator)?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<usize, SizeError> {
+        SizeCompound::end(self)
+    }
+}
+
+impl<'a> ser::SerializeStruct for SizeCompound<'a> {
+    type Ok = usize;
+    type Error = SizeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), SizeError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<usize, SizeError> {
+        SizeCompound::end(self)
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for SizeCompound<'a> {
+    type Ok = usize;
+    type Error = SizeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), SizeError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<usize, SizeError> {
+        SizeCompound::end(self)
+    }
+}
+
+fn prefix(cost: Cost, number: u128) -> usize {
+    match cost {
+        Cost::Fixed(size) => size,
+        Cost::Varint => varint_len(number),
+        Cost::Text => unreachable!("text isn't a prefix"),
+    }
+}
+
+fn varint_len(number: u128) -> usize {
+    let bits = 128 - number.leading_zeros() as usize;
+
+    bits.max(1).div_ceil(7)
+}
+
+fn zigzag(v: i128) -> u128 {
+    ((v << 1) ^ (v >> 127)) as u128
+}
+
+fn text_len(value: ValueRef<'_>) -> usize {
+    match value {
+        ValueRef::Bool(v) => display_len(v),
+        ValueRef::I8(v) => display_len(v),
+        ValueRef::I16(v) => display_len(v),
+        ValueRef::I32(v) => display_len(v),
+        ValueRef::I64(v) => display_len(v),
+        ValueRef::I128(v) => display_len(v),
+        ValueRef::U8(v) => display_len(v),
+        ValueRef::U16(v) => display_len(v),
+        ValueRef::U32(v) => display_len(v),
+        ValueRef::U64(v) => display_len(v),
+        ValueRef::U128(v) => display_len(v),
+        ValueRef::F32(v) if v.is_finite() => display_len(format_args!("{:?}", v)),
+        ValueRef::F64(v) if v.is_finite() => display_len(format_args!("{:?}", v)),
+        ValueRef::Char(v) => v.len_utf8() + 2,
+        ValueRef::Str(v) => v.len() + 2,
+        //[1,2,3]
+        ValueRef::Bytes(v) => 2 + v.len().saturating_sub(1) + v.iter().map(display_len).sum::<usize>(),
+        ValueRef::UnitVariant { variant, .. } => variant.len() + 2,
+        //null, also for non-finite floats
+        _ => 4,
+    }
+}
+
+pub(crate) fn display_len(value: impl fmt::Display) -> usize {
+    struct Counter(usize);
+
+    impl Write for Counter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0 += s.len();
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    let _ = write!(counter, "{}", value);
+    counter.0
+}
@@ -0,0 +1,69 @@
+use crate::ValueKind;
+use core::{cell::Cell, fmt};
+
+/// Call counters of serialize methods indexed by [ValueKind].
+///
+/// Counters are [Cell]s, so a serializer can hold `&Stats` and record calls from `self`.
+/// Methods generated with `counted(stats)` option of `impl_serialize!` call [`record`](Stats::record).
+/// # Example
+#[doc = include_str!("../docs/stats.md")]
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    counts: [Cell<u64>; ValueKind::ALL.len()],
+}
+
+impl Stats {
+    /// Stats with every counter at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Increments the counter of `kind`.
+    pub fn record(&self, kind: ValueKind) {
+        let count = &self.counts[kind as usize];
+
+        count.set(count.get() + 1);
+    }
+
+    /// Number of calls recorded for `kind`.
+    pub fn get(&self, kind: ValueKind) -> u64 {
+        self.counts[kind as usize].get()
+    }
+
+    /// Number of calls recorded for every kind.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().map(Cell::get).sum()
+    }
+
+    /// Kinds with at least one recorded call and their counts, in order of [`ValueKind::ALL`].
+    pub fn iter(&self) -> impl Iterator<Item = (ValueKind, u64)> + '_ {
+        ValueKind::ALL
+            .iter()
+            .map(move |&kind| (kind, self.get(kind)))
+            .filter(|&(_, count)| count > 0)
+    }
+
+    /// Sets every counter to zero.
+    pub fn reset(&self) {
+        for count in &self.counts {
+            count.set(0);
+        }
+    }
+
+    /// Prints counts by `value_type` to stdout.
+    #[cfg(feature = "std")]
+    pub fn report(&self) {
+        print!("{}", self);
+    }
+}
+
+/// One line `value_type: count` per kind with recorded calls.
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (kind, count) in self.iter() {
+            writeln!(f, "{}: {}", kind, count)?;
+        }
+
+        Ok(())
+    }
+}
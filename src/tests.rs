@@ -28,5 +28,44 @@ impl serde::ser::Error for SerializationError {
     }
 }
 
+#[derive(Debug, Error, PartialEq)]
+enum DeserializationError {
+    #[error("Cannot deserialize value to {0}")]
+    CannotDeserializeTo(String),
+    #[error("Custom({0})")]
+    Custom(String),
+}
+
+impl serde::de::Error for DeserializationError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: std::fmt::Display,
+    {
+        DeserializationError::Custom(msg.to_string())
+    }
+}
+
+mod attributes;
+mod compound;
+mod conformance;
+mod deserialize;
+mod diff;
 mod general;
-mod metavariables;
\ No newline at end of file
+mod handler;
+mod hash;
+mod hooks;
+mod map_key;
+mod metavariables;
+mod non_finite;
+mod path;
+mod registry;
+mod recording;
+mod rejecting;
+mod shape;
+mod size;
+mod stats;
+mod validate;
+mod value;
+mod value_de;
+mod visit;
+mod wide_integers;
\ No newline at end of file
@@ -0,0 +1,57 @@
+use super::*;
+
+#[test]
+fn track_caller() {
+    #[derive(Clone, Copy)]
+    struct LineSerializer;
+
+    impl ser::Serializer for LineSerializer {
+        type Error = SerializationError;
+        type Ok = u32;
+
+        type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+        impl_serialize!(
+            /// Returns line of the caller.
+            #[inline]
+            #[track_caller]
+            Ok(core::panic::Location::caller().line()),
+            [i8, i16, i32, i64]
+        );
+
+        impl_serialize!(#[cold] #[inline(never)] Err(SerializationError::CannotSerialize), [
+            bool,
+            bytes,
+            i128,
+            u8, u16, u32, u64, u128,
+            f32, f64,
+            char,
+            str,
+            none, some, unit,
+            unit_struct, unit_variant,
+            newtype_struct, newtype_variant,
+            seq, map,
+            tuple, tuple_struct, tuple_variant,
+            struct, struct_variant
+        ]);
+    }
+
+    let serializer = LineSerializer;
+
+    let (caller_line, line) = (serializer.serialize_i8(4).ok().unwrap(), line!());
+    assert_eq!(caller_line, line);
+
+    let (caller_line, line) = (serializer.serialize_i64(4).ok().unwrap(), line!());
+    assert_eq!(caller_line, line);
+
+    assert_eq!(
+        serializer.serialize_u8(4).err().unwrap(),
+        SerializationError::CannotSerialize
+    );
+}
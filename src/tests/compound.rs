@@ -0,0 +1,96 @@
+use super::*;
+use crate::{Registry, Rejecting};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy)]
+struct PairSerializer;
+
+impl_serialize! {
+    impl ser::Serializer for PairSerializer {
+        type Error = SerializationError;
+        type Ok = &'static str;
+
+        type SerializeMap = Rejecting<Self::Ok, Self::Error>;
+        type SerializeSeq = Rejecting<Self::Ok, Self::Error>;
+        type SerializeStruct = Rejecting<Self::Ok, Self::Error>;
+        type SerializeStructVariant = Rejecting<Self::Ok, Self::Error>;
+        type SerializeTuple = Rejecting<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = Rejecting<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = Rejecting<Self::Ok, Self::Error>;
+
+        impl_serialize!(
+            value: Ok(value_type),
+            compound: Ok(Rejecting::new(value_type).end_with(value_type)),
+            all
+        );
+    }
+}
+
+#[test]
+fn value_and_compound() {
+    assert_eq!(PairSerializer.serialize_bool(true), Ok("bool"));
+    assert_eq!(PairSerializer.serialize_u128(1), Ok("u128"));
+    assert_eq!(PairSerializer.serialize_some(&1), Ok("some"));
+    assert_eq!(PairSerializer.serialize_newtype_variant("E", 0, "A", &1), Ok("newtype_variant"));
+
+    assert_eq!(Vec::<u8>::new().serialize(PairSerializer), Ok("seq"));
+    assert_eq!(BTreeMap::<u8, u8>::new().serialize(PairSerializer), Ok("map"));
+    assert_eq!(
+        vec![1].serialize(PairSerializer),
+        Err(SerializationError::Custom("cannot serialize i32".to_string()))
+    );
+}
+
+#[test]
+fn value_and_compound_registration() {
+    let registrations = PairSerializer::IMPLEMENTED_BY_MACRO;
+
+    assert_eq!(registrations.len(), 1);
+    assert_eq!(registrations[0].kinds.len(), 30);
+    assert_eq!(
+        registrations[0].label,
+        "value: Ok(value_type), compound: Ok(Rejecting::new(value_type).end_with(value_type))"
+    );
+}
+
+#[test]
+fn values_and_compounds() {
+    struct SplitSerializer;
+
+    impl_serialize! {
+        impl ser::Serializer for SplitSerializer {
+            type Error = SerializationError;
+            type Ok = ();
+
+            type SerializeMap = Rejecting<Self::Ok, Self::Error>;
+            type SerializeSeq = Rejecting<Self::Ok, Self::Error>;
+            type SerializeStruct = Rejecting<Self::Ok, Self::Error>;
+            type SerializeStructVariant = Rejecting<Self::Ok, Self::Error>;
+            type SerializeTuple = Rejecting<Self::Ok, Self::Error>;
+            type SerializeTupleStruct = Rejecting<Self::Ok, Self::Error>;
+            type SerializeTupleVariant = Rejecting<Self::Ok, Self::Error>;
+
+            impl_serialize!(
+                Err(SerializationError::CannotSerializeFrom(value_type.to_string())),
+                values
+            );
+
+            impl_serialize!(#[cold] Ok(Rejecting::new(value_type)), compounds);
+        }
+    }
+
+    assert_eq!(
+        SplitSerializer.serialize_unit_struct("U").err().unwrap(),
+        SerializationError::CannotSerializeFrom("unit_struct".to_string())
+    );
+
+    assert_eq!(
+        (1, 2).serialize(SplitSerializer).err().unwrap(),
+        SerializationError::Custom("cannot serialize i32".to_string())
+    );
+
+    let kinds: Vec<_> = SplitSerializer::IMPLEMENTED_BY_MACRO.iter().map(|registration| registration.kinds.len()).collect();
+
+    assert_eq!(kinds, [23, 7]);
+}
@@ -0,0 +1,84 @@
+use super::*;
+use crate::check_serializer;
+
+#[derive(Clone, Copy)]
+struct IntSerializer;
+
+impl ser::Serializer for IntSerializer {
+    type Error = SerializationError;
+    type Ok = ();
+
+    type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+    impl_serialize!(Ok(()), [i8, i16, i32, i64, i128]);
+
+    impl_serialize!(Err(SerializationError::CannotSerializeFrom(value_type.to_string())), [
+        bool,
+        bytes,
+        u8, u16, u32, u64, u128,
+        f32, f64,
+        char,
+        str,
+        none, some, unit,
+        unit_struct, unit_variant,
+        newtype_struct, newtype_variant,
+        seq, map,
+        tuple, tuple_struct, tuple_variant,
+        struct, struct_variant
+    ]);
+}
+
+test_serializer!(IntSerializer, accept [i8, i16, i32, i64, i128], reject [_]);
+
+test_serializer!(rejects_unsigned: IntSerializer, accept [], reject [u8, u16, u32, u64, u128]);
+
+test_serializer!(accepts_signed: IntSerializer, accept [_], reject [
+    bool,
+    bytes,
+    u8, u16, u32, u64, u128,
+    f32, f64,
+    char,
+    str,
+    none, some, unit,
+    unit_struct, unit_variant,
+    newtype_struct, newtype_variant,
+    seq, map,
+    tuple, tuple_struct, tuple_variant,
+    struct, struct_variant
+]);
+
+#[test]
+fn mismatch() {
+    let message = std::panic::catch_unwind(|| {
+        check_serializer(|| IntSerializer, &["i32", "str"], &["_"])
+    })
+    .err()
+    .unwrap()
+    .downcast::<String>()
+    .unwrap();
+
+    assert_eq!(
+        *message,
+        [
+            "serializer conformance failed:",
+            "    i8: expected Err, found Ok",
+            "    i16: expected Err, found Ok",
+            "    i64: expected Err, found Ok",
+            "    i128: expected Err, found Ok",
+            "    str: expected Ok, found Err(Cannot serialize value from str)",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+#[should_panic(expected = "unknown value_type `string`")]
+fn unknown_value_type() {
+    check_serializer(|| IntSerializer, &["string"], &[]);
+}
@@ -0,0 +1,173 @@
+use super::*;
+use serde::de::{self, Deserializer};
+
+#[test]
+fn all_value_type() {
+    #[derive(Clone, Copy)]
+    struct MyDeserializer;
+
+    impl<'de> de::Deserializer<'de> for MyDeserializer {
+        type Error = DeserializationError;
+
+        impl_deserialize!(Err(DeserializationError::CannotDeserializeTo(value_type.to_string())), [
+            any,
+            bool,
+            i8, i16, i32, i64, i128,
+            u8, u16, u32, u64, u128,
+            f32, f64,
+            char, str, string,
+            bytes, byte_buf,
+            option, unit,
+            unit_struct, newtype_struct,
+            seq, tuple, tuple_struct,
+            map, struct, enum,
+            identifier, ignored_any
+        ]);
+    }
+
+    let deserializer = MyDeserializer;
+
+    assert_eq!(
+        deserializer.deserialize_any(de::IgnoredAny).err().unwrap(),
+        DeserializationError::CannotDeserializeTo("any".to_string())
+    );
+
+    assert_eq!(
+        deserializer.deserialize_i128(de::IgnoredAny).err().unwrap(),
+        DeserializationError::CannotDeserializeTo("i128".to_string())
+    );
+
+    assert_eq!(
+        deserializer.deserialize_byte_buf(de::IgnoredAny).err().unwrap(),
+        DeserializationError::CannotDeserializeTo("byte_buf".to_string())
+    );
+
+    assert_eq!(
+        deserializer.deserialize_option(de::IgnoredAny).err().unwrap(),
+        DeserializationError::CannotDeserializeTo("option".to_string())
+    );
+
+    assert_eq!(
+        deserializer
+            .deserialize_tuple_struct("name", 2, de::IgnoredAny)
+            .err()
+            .unwrap(),
+        DeserializationError::CannotDeserializeTo("tuple_struct".to_string())
+    );
+
+    assert_eq!(
+        deserializer
+            .deserialize_enum("name", &["a", "b"], de::IgnoredAny)
+            .err()
+            .unwrap(),
+        DeserializationError::CannotDeserializeTo("enum".to_string())
+    );
+
+    assert_eq!(
+        deserializer.deserialize_identifier(de::IgnoredAny).err().unwrap(),
+        DeserializationError::CannotDeserializeTo("identifier".to_string())
+    );
+
+    assert_eq!(
+        deserializer.deserialize_ignored_any(de::IgnoredAny).err().unwrap(),
+        DeserializationError::CannotDeserializeTo("ignored_any".to_string())
+    );
+}
+
+#[test]
+fn arguments() {
+    #[derive(Clone, Copy)]
+    struct ShapeDeserializer;
+
+    impl<'de> de::Deserializer<'de> for ShapeDeserializer {
+        type Error = DeserializationError;
+
+        impl_deserialize!(visitor.visit_u64(len as u64), [tuple, tuple_struct]);
+
+        impl_deserialize!(visitor.visit_str(name), [unit_struct, newtype_struct]);
+
+        impl_deserialize!(visitor.visit_u64(fields.len() as u64), struct);
+
+        impl_deserialize!(visitor.visit_str(variants[0]), enum);
+
+        impl_deserialize!(Err(DeserializationError::CannotDeserializeTo(value_type.to_string())), [
+            any,
+            bool,
+            i8, i16, i32, i64, i128,
+            u8, u16, u32, u64, u128,
+            f32, f64,
+            char, str, string,
+            bytes, byte_buf,
+            option, unit,
+            seq, map,
+            identifier, ignored_any
+        ]);
+    }
+
+    struct U64Visitor;
+
+    impl<'de> de::Visitor<'de> for U64Visitor {
+        type Value = u64;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("u64")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+    }
+
+    struct StrVisitor;
+
+    impl<'de> de::Visitor<'de> for StrVisitor {
+        type Value = String;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("str")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Ok(v.to_string())
+        }
+    }
+
+    let deserializer = ShapeDeserializer;
+
+    assert_eq!(
+        deserializer.deserialize_tuple(3, U64Visitor).ok().unwrap(),
+        3
+    );
+
+    assert_eq!(
+        deserializer
+            .deserialize_tuple_struct("name", 5, U64Visitor)
+            .ok()
+            .unwrap(),
+        5
+    );
+
+    assert_eq!(
+        deserializer
+            .deserialize_struct("name", &["a", "b"], U64Visitor)
+            .ok()
+            .unwrap(),
+        2
+    );
+
+    assert_eq!(
+        deserializer
+            .deserialize_unit_struct("Unit", StrVisitor)
+            .ok()
+            .unwrap(),
+        "Unit"
+    );
+
+    assert_eq!(
+        deserializer
+            .deserialize_enum("name", &["First", "Second"], StrVisitor)
+            .ok()
+            .unwrap(),
+        "First"
+    );
+}
@@ -0,0 +1,155 @@
+use crate::{diff, Change, Difference, Value};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+enum Mode {
+    Fast,
+    Limited(u32),
+    Range(u8, u8),
+    Named { id: u64 },
+}
+
+#[derive(Serialize)]
+struct Item {
+    name: String,
+    port: Option<u16>,
+}
+
+#[derive(Serialize)]
+struct Config {
+    items: Vec<Item>,
+    mode: Mode,
+    limits: BTreeMap<&'static str, u8>,
+}
+
+fn item(name: &str, port: Option<u16>) -> Item {
+    Item { name: name.to_string(), port }
+}
+
+fn lines<L: Serialize, R: Serialize>(left: &L, right: &R) -> Vec<String> {
+    diff(left, right).unwrap().iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn equal() {
+    assert_eq!(diff(&vec![1, 2], &vec![1, 2]).unwrap(), []);
+    assert_eq!(lines(&vec![1, 2], &[1, 2]), [".: seq != tuple"]);
+}
+
+#[test]
+fn root() {
+    assert_eq!(
+        diff(&1u8, &2u8).unwrap(),
+        [Difference {
+            path: String::new(),
+            change: Change::Value { left: Value::U8(1), right: Value::U8(2) }
+        }]
+    );
+    assert_eq!(lines(&1u8, &"1"), [".: u8 != str"]);
+}
+
+#[test]
+fn nested() {
+    let left = Config {
+        items: vec![item("a", Some(80)), item("b", None), item("c", Some(1))],
+        mode: Mode::Limited(1),
+        limits: BTreeMap::from([("cpu", 1), ("mem", 2)]),
+    };
+    let right = Config {
+        items: vec![item("a", Some(81)), item("x", Some(1))],
+        mode: Mode::Limited(2),
+        limits: BTreeMap::from([("cpu", 1), ("disk", 3)]),
+    };
+
+    assert_eq!(
+        lines(&left, &right),
+        [
+            ".items[0].port: 80 != 81",
+            ".items[1].name: \"b\" != \"x\"",
+            ".items[1].port: none != some",
+            ".items[2]: missing on the right (Item { name: \"c\", port: Some(1) })",
+            ".mode: 1 != 2",
+            ".limits[\"mem\"]: missing on the right (2)",
+            ".limits[\"disk\"]: missing on the left (3)",
+        ]
+    );
+}
+
+#[test]
+fn enums() {
+    assert_eq!(lines(&Mode::Fast, &Mode::Limited(1)), [".: unit_variant != newtype_variant"]);
+    assert_eq!(lines(&Mode::Range(1, 2), &Mode::Range(1, 3)), ["[1]: 2 != 3"]);
+    assert_eq!(lines(&Mode::Named { id: 1 }, &Mode::Named { id: 2 }), [".id: 1 != 2"]);
+    assert_eq!(
+        lines(&Mode::Limited(1), &Mode::Named { id: 1 }),
+        [".: newtype_variant != struct_variant"]
+    );
+}
+
+#[test]
+fn lengths() {
+    struct Unsized;
+
+    impl Serialize for Unsized {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq([1u8].iter().filter(|_| true))
+        }
+    }
+
+    struct Skipping;
+
+    impl Serialize for Skipping {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut r#struct = serializer.serialize_struct("Item", 2)?;
+            serde::ser::SerializeStruct::serialize_field(&mut r#struct, "name", "a")?;
+            serde::ser::SerializeStruct::skip_field(&mut r#struct, "port")?;
+            serde::ser::SerializeStruct::end(r#struct)
+        }
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename = "Item")]
+    struct Named {
+        name: &'static str,
+    }
+
+    //contents are equal, only serialize_seq and serialize_struct got different len
+    assert_eq!(diff(&Unsized, &vec![1u8]).unwrap(), []);
+    assert_eq!(diff(&Skipping, &Named { name: "a" }).unwrap(), []);
+    assert_eq!(lines(&Unsized, &vec![2u8]), ["[0]: 1 != 2"]);
+}
+
+#[test]
+fn floats() {
+    assert_eq!(diff(&f64::NAN, &f64::NAN).unwrap(), []);
+    assert_eq!(diff(&vec![f32::NAN], &vec![f32::NAN]).unwrap(), []);
+    assert_eq!(lines(&1.5f64, &f64::NAN), [".: 1.5 != NaN"]);
+    assert_eq!(lines(&0.0f64, &-0.0f64), [".: 0.0 != -0.0"]);
+    assert_eq!(lines(&(0.0f32,), &(-0.0f32,)), ["[0]: 0.0 != -0.0"]);
+}
+
+#[test]
+fn types() {
+    #[derive(Serialize)]
+    struct Port {
+        port: u16,
+    }
+
+    #[derive(Serialize)]
+    struct TextPort {
+        port: &'static str,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename = "Port")]
+    struct RenamedPort {
+        port: &'static str,
+    }
+
+    assert_eq!(
+        lines(&Port { port: 1 }, &TextPort { port: "1" }),
+        [".: Port { port: 1 } != TextPort { port: \"1\" }"]
+    );
+    assert_eq!(lines(&Port { port: 1 }, &RenamedPort { port: "1" }), [".port: u16 != str"]);
+}
@@ -0,0 +1,165 @@
+use super::*;
+use crate::{Erased, Handler, ValueKind, ValueRef};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+struct DescribingHandler;
+
+impl Handler for DescribingHandler {
+    type Ok = String;
+    type Error = SerializationError;
+
+    fn handle(self, kind: ValueKind, value: ValueRef<'_>) -> Result<String, SerializationError> {
+        assert_eq!(kind, value.kind());
+
+        match value {
+            ValueRef::Bool(v) => Ok(v.to_string()),
+            ValueRef::I128(v) => Ok(v.to_string()),
+            ValueRef::Str(v) => Ok(format!("{:?}", v)),
+            ValueRef::Bytes(v) => Ok(format!("{:?}", v)),
+            ValueRef::None => Ok("none".to_string()),
+            ValueRef::Some(value) => Ok(format!("some of {:?}", value.kind())),
+            ValueRef::UnitVariant { name, variant_index, variant } => Ok(format!("{}::{} ({})", name, variant, variant_index)),
+            ValueRef::NewtypeStruct { name, value } => Ok(format!("{} of {:?}", name, value.kind())),
+            ValueRef::NewtypeVariant { name, variant, value, .. } => Ok(format!("{}::{} of {:?}", name, variant, value.kind())),
+            ValueRef::Seq { len } => Ok(format!("seq of {:?}", len)),
+            ValueRef::StructVariant { name, variant, len, .. } => Ok(format!("{}::{} with {}", name, variant, len)),
+            _ => Err(SerializationError::CannotSerializeFrom(kind.to_string())),
+        }
+    }
+}
+
+#[test]
+fn values() {
+    let serializer = || DescribingHandler.serializer();
+
+    assert_eq!(true.serialize(serializer()).unwrap(), "true");
+    assert_eq!((-1i128).serialize(serializer()).unwrap(), "-1");
+    assert_eq!("text".serialize(serializer()).unwrap(), "\"text\"");
+    assert_eq!(serializer().serialize_bytes(&[1, 2]).unwrap(), "[1, 2]");
+    assert_eq!(None::<u8>.serialize(serializer()).unwrap(), "none");
+    assert_eq!(Some("text").serialize(serializer()).unwrap(), "some of Some(Str)");
+    assert_eq!(serializer().serialize_unit_variant("E", 2, "C").unwrap(), "E::C (2)");
+    assert_eq!(serializer().serialize_newtype_struct("N", &1u8).unwrap(), "N of Some(U8)");
+    assert_eq!(serializer().serialize_newtype_variant("E", 0, "A", &()).unwrap(), "E::A of Some(Unit)");
+
+    assert_eq!(
+        'c'.serialize(serializer()).err().unwrap(),
+        SerializationError::CannotSerializeFrom("char".to_string())
+    );
+}
+
+#[test]
+fn compounds() {
+    let serializer = || DescribingHandler.serializer();
+
+    assert_eq!(Vec::<u8>::new().serialize(serializer()).unwrap(), "seq of Some(0)");
+    assert_eq!(
+        ser::SerializeStructVariant::end(serializer().serialize_struct_variant("E", 1, "B", 0).unwrap()).unwrap(),
+        "E::B with 0"
+    );
+
+    assert_eq!(
+        vec![1].serialize(serializer()).err().unwrap(),
+        SerializationError::Custom("cannot serialize i32".to_string())
+    );
+
+    assert_eq!(
+        BTreeMap::<u8, u8>::new().serialize(serializer()).err().unwrap(),
+        SerializationError::CannotSerializeFrom("map".to_string())
+    );
+}
+
+#[test]
+fn rejected_by_default() {
+    struct AnyHandler;
+
+    impl Handler for AnyHandler {
+        type Ok = ValueKind;
+        type Error = SerializationError;
+
+        fn handle(self, kind: ValueKind, _value: ValueRef<'_>) -> Result<ValueKind, SerializationError> {
+            Ok(kind)
+        }
+    }
+
+    //the handler accepts every value, but has no callbacks for elements of compound values
+    assert_eq!(Vec::<u8>::new().serialize(AnyHandler.serializer()).unwrap(), ValueKind::Seq);
+    assert_eq!(
+        BTreeMap::from([(1u8, 2u8)]).serialize(AnyHandler.serializer()).err().unwrap(),
+        SerializationError::Custom("cannot serialize u8".to_string())
+    );
+    assert_eq!(
+        (1u8, 2u8).serialize(AnyHandler.serializer()).err().unwrap(),
+        SerializationError::Custom("cannot serialize u8".to_string())
+    );
+}
+
+struct ListingHandler;
+
+impl Handler for ListingHandler {
+    type Ok = String;
+    type Error = SerializationError;
+
+    fn handle(self, kind: ValueKind, value: ValueRef<'_>) -> Result<String, SerializationError> {
+        match value {
+            ValueRef::U8(v) => Ok(v.to_string()),
+            ValueRef::Str(v) => Ok(format!("{:?}", v)),
+            ValueRef::Seq { .. } | ValueRef::Tuple { .. } => Ok("[".to_string()),
+            ValueRef::Map { .. } => Ok("{".to_string()),
+            ValueRef::Struct { name, .. } => Ok(format!("{} {{", name)),
+            _ => Err(SerializationError::CannotSerializeFrom(kind.to_string())),
+        }
+    }
+
+    fn element(list: &mut String, value: Erased<'_>) -> Result<(), SerializationError> {
+        list.push_str(&value.serialize(ListingHandler.serializer())?);
+        list.push(',');
+        Ok(())
+    }
+
+    fn field(list: &mut String, key: &'static str, value: Erased<'_>) -> Result<(), SerializationError> {
+        list.push_str(key);
+        list.push(':');
+        Self::element(list, value)
+    }
+
+    fn key(list: &mut String, key: Erased<'_>) -> Result<(), SerializationError> {
+        list.push_str(&key.serialize(ListingHandler.serializer())?);
+        list.push(':');
+        Ok(())
+    }
+
+    fn value(list: &mut String, value: Erased<'_>) -> Result<(), SerializationError> {
+        Self::element(list, value)
+    }
+
+    fn end(mut list: String) -> Result<String, SerializationError> {
+        let close = if list.starts_with('[') { ']' } else { '}' };
+        list.push(close);
+        Ok(list)
+    }
+}
+
+#[test]
+fn callbacks() {
+    #[derive(Serialize)]
+    struct Point {
+        x: u8,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        y: Option<u8>,
+    }
+
+    let serializer = || ListingHandler.serializer();
+
+    assert_eq!(vec![1u8, 2].serialize(serializer()).unwrap(), "[1,2,]");
+    assert_eq!((3u8, vec![4u8]).serialize(serializer()).unwrap(), "[3,[4,],]");
+    assert_eq!(BTreeMap::from([("a", 1u8)]).serialize(serializer()).unwrap(), "{\"a\":1,}");
+    assert_eq!(Point { x: 5, y: None }.serialize(serializer()).unwrap(), "Point {x:5,}");
+
+    //errors of the elements are returned
+    assert_eq!(
+        vec!['c'].serialize(serializer()).err().unwrap(),
+        SerializationError::CannotSerializeFrom("char".to_string())
+    );
+}
@@ -0,0 +1,132 @@
+use crate::{stable_hash, FnvHasher, HashingSerializer};
+use serde::{ser::SerializeSeq, Serialize, Serializer};
+use std::{collections::BTreeMap, hash::Hasher};
+
+#[derive(Serialize)]
+struct A {
+    x: u8,
+}
+
+#[derive(Serialize)]
+struct B {
+    x: u8,
+}
+
+#[derive(Serialize)]
+struct Y {
+    y: u8,
+}
+
+#[derive(Serialize)]
+struct Newtype(u8);
+
+#[derive(Serialize)]
+enum First {
+    Unit,
+    Newtype(u8),
+}
+
+#[derive(Serialize)]
+enum Second {
+    Unit,
+}
+
+fn hash<T: ?Sized + Serialize>(value: &T) -> u64 {
+    stable_hash(value).unwrap()
+}
+
+macro_rules! assert_distinct {
+    ($($value:expr),+ $(,)?) => {{
+        let hashes = [$((stringify!($value), hash(&$value))),+];
+
+        for (i, (left, left_hash)) in hashes.iter().enumerate() {
+            for (right, right_hash) in &hashes[i + 1..] {
+                assert_ne!(left_hash, right_hash, "{} and {} collide", left, right);
+            }
+        }
+    }};
+}
+
+#[test]
+fn options_and_units() {
+    assert_distinct!(Some(()), (), None::<()>, Some(None::<()>), Some(Some(())), Second::Unit);
+}
+
+#[test]
+fn primitives() {
+    assert_distinct!(0u8, 0i8, 0u16, 0u64, false, 0.0f64, -0.0f64, 0.0f32, '\0', "", "\0");
+}
+
+#[test]
+fn strings() {
+    assert_distinct!(("ab", "c"), ("a", "bc"), ("abc", ""), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn compounds() {
+    assert_distinct!(
+        vec![vec![1], vec![]],
+        vec![vec![], vec![1]],
+        vec![vec![1]],
+        vec![1],
+        (1,),
+        Vec::<u8>::new(),
+    );
+
+    let map: BTreeMap<u8, u8> = [(1, 2)].into_iter().collect();
+    assert_distinct!(map, vec![1u8, 2], (1u8, 2u8), vec![(1u8, 2u8)]);
+}
+
+#[test]
+fn names_and_variants() {
+    assert_distinct!(A { x: 1 }, B { x: 1 }, Y { y: 1 }, (1u8,), Newtype(1), First::Newtype(1), 1u8);
+    assert_distinct!(First::Unit, Second::Unit);
+}
+
+#[test]
+fn equal_values() {
+    struct Unsized(Vec<u8>);
+
+    impl Serialize for Unsized {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(None)?;
+            for element in &self.0 {
+                seq.serialize_element(element)?;
+            }
+            seq.end()
+        }
+    }
+
+    assert_eq!(hash(&Unsized(vec![1, 2])), hash(&vec![1u8, 2]));
+    assert_eq!(hash(&A { x: 1 }), hash(&A { x: 1 }));
+    assert_eq!(hash(&format_args!("{}-{}", 1, 2)), hash("1-2"));
+}
+
+#[test]
+fn stable() {
+    //changes of these values break stored hashes
+    assert_eq!(FnvHasher::default().finish(), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(hash(&1u8), STABLE_U8);
+    assert_eq!(hash(&A { x: 1 }), STABLE_A);
+    assert_eq!(hash("ab"), STABLE_STR);
+    assert_eq!(hash(&[1u16, 2][..]), STABLE_SEQ);
+    assert_eq!(hash(&First::Newtype(7)), STABLE_NEWTYPE_VARIANT);
+    assert_eq!(hash(&Some(true)), STABLE_SOME);
+}
+
+#[test]
+fn any_hasher() {
+    let mut left = std::collections::hash_map::DefaultHasher::new();
+    let mut right = left.clone();
+
+    Some(()).serialize(HashingSerializer::new(&mut left)).unwrap();
+    ().serialize(HashingSerializer::new(&mut right)).unwrap();
+    assert_ne!(left.finish(), right.finish());
+}
+
+const STABLE_U8: u64 = 588769818076096488;
+const STABLE_A: u64 = 278114028777428011;
+const STABLE_STR: u64 = 2080402116368624284;
+const STABLE_SEQ: u64 = 14835265262630656380;
+const STABLE_NEWTYPE_VARIANT: u64 = 11081379793378472145;
+const STABLE_SOME: u64 = 6573927352041413999;
@@ -0,0 +1,90 @@
+use super::*;
+use crate::Rejecting;
+use serde::Serialize;
+use std::cell::{Cell, RefCell};
+
+#[test]
+fn before_and_after() {
+    struct LoggingSerializer<'a> {
+        log: &'a RefCell<Vec<String>>,
+        depth: &'a Cell<usize>,
+    }
+
+    impl<'a> ser::Serializer for LoggingSerializer<'a> {
+        type Error = SerializationError;
+        type Ok = usize;
+
+        type SerializeMap = Rejecting<Self::Ok, Self::Error>;
+        type SerializeSeq = Rejecting<Self::Ok, Self::Error>;
+        type SerializeStruct = Rejecting<Self::Ok, Self::Error>;
+        type SerializeStructVariant = Rejecting<Self::Ok, Self::Error>;
+        type SerializeTuple = Rejecting<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = Rejecting<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = Rejecting<Self::Ok, Self::Error>;
+
+        impl_serialize!(
+            before = {
+                let depth = self.depth.get() + 1;
+                self.depth.set(depth);
+            },
+            after = |result| {
+                self.depth.set(depth - 1);
+                self.log.borrow_mut().push(format!("{} at {}: {}", value_type, depth, result.is_ok()));
+                result
+            },
+            value: Ok(depth),
+            compound: Ok(Rejecting::new(value_type).end_with(depth)),
+            all
+        );
+    }
+
+    let log = RefCell::new(Vec::new());
+    let depth = Cell::new(0);
+    let serializer = || LoggingSerializer { log: &log, depth: &depth };
+
+    assert_eq!(serializer().serialize_i32(4), Ok(1));
+    assert_eq!(Vec::<u8>::new().serialize(serializer()), Ok(1));
+    assert!(vec![1].serialize(serializer()).is_err());
+    assert_eq!(depth.get(), 0);
+
+    assert_eq!(
+        log.into_inner(),
+        ["i32 at 1: true", "seq at 1: true", "seq at 1: true"]
+    );
+}
+
+#[test]
+fn before_expression() {
+    struct CountingSerializer<'a> {
+        count: &'a Cell<usize>,
+    }
+
+    impl<'a> ser::Serializer for CountingSerializer<'a> {
+        type Error = SerializationError;
+        type Ok = ();
+
+        type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+        impl_serialize!(
+            #[inline]
+            before = { self.count.set(self.count.get() + 1) },
+            Err(SerializationError::CannotSerializeFrom(value_type.to_string())),
+            all
+        );
+    }
+
+    let count = Cell::new(0);
+
+    assert_eq!(
+        CountingSerializer { count: &count }.serialize_bool(true).err().unwrap(),
+        SerializationError::CannotSerializeFrom("bool".to_string())
+    );
+    assert!(CountingSerializer { count: &count }.serialize_seq(None).is_err());
+    assert_eq!(count.get(), 2);
+}
@@ -0,0 +1,65 @@
+use crate::{MapKeySerializer, ValueKind};
+use serde::{Serialize, Serializer};
+use std::{borrow::Cow, net::Ipv4Addr};
+
+#[derive(Serialize)]
+enum Mode {
+    Fast,
+    Limited(u32),
+}
+
+#[derive(Serialize)]
+struct Name(&'static str);
+
+#[derive(Serialize)]
+struct Unit;
+
+fn key<T: Serialize>(value: &T, serializer: MapKeySerializer) -> Result<Cow<'static, str>, String> {
+    value.serialize(serializer).map_err(|error| error.to_string())
+}
+
+#[test]
+fn defaults() {
+    let keys = MapKeySerializer::new();
+
+    assert_eq!(keys, MapKeySerializer::default());
+    assert_eq!(key(&"a", keys).unwrap(), "a");
+    assert_eq!(key(&'c', keys).unwrap(), "c");
+    assert_eq!(key(&-7i64, keys).unwrap(), "-7");
+    assert_eq!(key(&u128::MAX, keys).unwrap(), u128::MAX.to_string());
+    assert_eq!(key(&Name("b"), keys).unwrap(), "b");
+    assert_eq!(key(&Ipv4Addr::LOCALHOST, keys).unwrap(), "127.0.0.1");
+
+    assert_eq!(key(&true, keys).unwrap_err(), "cannot use bool as map key");
+    assert_eq!(key(&Mode::Fast, keys).unwrap_err(), "cannot use unit_variant as map key");
+}
+
+#[test]
+fn options() {
+    let keys = MapKeySerializer::new().integers(false).unit_variants(true).bools(true);
+
+    assert_eq!(key(&5u8, keys).unwrap_err(), "cannot use u8 as map key");
+    assert_eq!(key(&false, keys).unwrap(), "false");
+    assert!(matches!(key(&Mode::Fast, keys).unwrap(), Cow::Borrowed("Fast")));
+    assert!(matches!(key(&"a", keys).unwrap(), Cow::Owned(_)));
+}
+
+#[test]
+fn rejected() {
+    let keys = MapKeySerializer::new().unit_variants(true).bools(true);
+    let rejected: [(ValueKind, Result<Cow<'static, str>, String>); 9] = [
+        (ValueKind::F64, key(&1.5, keys)),
+        (ValueKind::None, key(&None::<u8>, keys)),
+        (ValueKind::Some, key(&Some(1), keys)),
+        (ValueKind::Unit, key(&(), keys)),
+        (ValueKind::UnitStruct, key(&Unit, keys)),
+        (ValueKind::NewtypeVariant, key(&Mode::Limited(1), keys)),
+        (ValueKind::Seq, key(&vec![1], keys)),
+        (ValueKind::Tuple, key(&(1, 2), keys)),
+        (ValueKind::Bytes, keys.serialize_bytes(b"a").map_err(|error| error.to_string())),
+    ];
+
+    for (kind, result) in rejected {
+        assert_eq!(result.unwrap_err(), format!("cannot use {} as map key", kind));
+    }
+}
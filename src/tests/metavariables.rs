@@ -304,3 +304,151 @@ fn more_than_one() {
         }
     );
 }
+
+#[test]
+fn unexpected() {
+    #[derive(Clone, Copy)]
+    struct UnexpectedSerializer;
+
+    impl ser::Serializer for UnexpectedSerializer {
+        type Error = SerializationError;
+        type Ok = ();
+
+        type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+        impl_serialize!(Err(SerializationError::CannotSerializeFrom(unexpected.to_string())), [
+            bool,
+            bytes,
+            i8, i16, i32, i64,
+            u8, u16, u32, u64,
+            f32, f64,
+            char,
+            str,
+            none, some, unit,
+            unit_struct, unit_variant,
+            newtype_struct, newtype_variant,
+            seq, map,
+            tuple, tuple_struct, tuple_variant,
+            struct, struct_variant
+        ]);
+    }
+
+    let serializer = UnexpectedSerializer;
+
+    assert_eq!(
+        serializer.serialize_bool(true).err().unwrap(),
+        SerializationError::CannotSerializeFrom("boolean `true`".to_string())
+    );
+
+    assert_eq!(
+        serializer.serialize_i16(-4).err().unwrap(),
+        SerializationError::CannotSerializeFrom("integer `-4`".to_string())
+    );
+
+    assert_eq!(
+        serializer.serialize_u8(4).err().unwrap(),
+        SerializationError::CannotSerializeFrom("integer `4`".to_string())
+    );
+
+    assert_eq!(
+        serializer.serialize_f32(0.5).err().unwrap(),
+        SerializationError::CannotSerializeFrom("floating point `0.5`".to_string())
+    );
+
+    assert_eq!(
+        serializer.serialize_str("str").err().unwrap(),
+        SerializationError::CannotSerializeFrom("string \"str\"".to_string())
+    );
+
+    assert_eq!(
+        serializer.serialize_some(&1).err().unwrap(),
+        SerializationError::CannotSerializeFrom("Option value".to_string())
+    );
+
+    assert_eq!(
+        serializer
+            .serialize_unit_variant("struct", 5, "variant")
+            .err()
+            .unwrap(),
+        SerializationError::CannotSerializeFrom("unit variant".to_string())
+    );
+
+    assert_eq!(
+        serializer.serialize_tuple(5).err().unwrap(),
+        SerializationError::CannotSerializeFrom("sequence".to_string())
+    );
+
+    assert_eq!(
+        serializer.serialize_struct("name", 1).err().unwrap(),
+        SerializationError::CannotSerializeFrom("map".to_string())
+    );
+}
+
+#[test]
+fn value_ref() {
+    use crate::ValueKind;
+
+    struct ValueRefSerializer;
+
+    impl ser::Serializer for ValueRefSerializer {
+        type Error = SerializationError;
+        type Ok = String;
+
+        type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+        impl_serialize!(
+            value: {
+                assert_eq!(value_ref.kind().as_str(), value_type);
+                Ok(format!("{:?}", value_ref))
+            },
+            compound: {
+                assert_eq!(value_ref.kind().as_str(), value_type);
+                Err(SerializationError::CannotSerializeFrom(format!("{:?}", value_ref)))
+            },
+            all
+        );
+    }
+
+    let serializer = || ValueRefSerializer;
+
+    assert_eq!(serializer().serialize_i16(-4).unwrap(), "I16(-4)");
+    assert_eq!(serializer().serialize_u128(4).unwrap(), "U128(4)");
+    assert_eq!(serializer().serialize_str("str").unwrap(), "Str(\"str\")");
+    assert_eq!(serializer().serialize_bytes(b"b").unwrap(), "Bytes([98])");
+    assert_eq!(serializer().serialize_none().unwrap(), "None");
+    assert_eq!(serializer().serialize_some(&1).unwrap(), "Some(Erased(Some(I32)))");
+    assert_eq!(serializer().serialize_unit_struct("U").unwrap(), "UnitStruct { name: \"U\" }");
+    assert_eq!(
+        serializer().serialize_unit_variant("E", 5, "V").unwrap(),
+        "UnitVariant { name: \"E\", variant_index: 5, variant: \"V\" }"
+    );
+    assert_eq!(
+        serializer().serialize_newtype_variant("E", 1, "V", "str").unwrap(),
+        "NewtypeVariant { name: \"E\", variant_index: 1, variant: \"V\", value: Erased(Some(Str)) }"
+    );
+
+    assert_eq!(
+        serializer().serialize_seq(Some(3)).err().unwrap(),
+        SerializationError::CannotSerializeFrom("Seq { len: Some(3) }".to_string())
+    );
+    assert_eq!(
+        serializer().serialize_struct_variant("E", 0, "V", 2).err().unwrap(),
+        SerializationError::CannotSerializeFrom(
+            "StructVariant { name: \"E\", variant_index: 0, variant: \"V\", len: 2 }".to_string()
+        )
+    );
+
+    assert_eq!(ValueKind::of(&Some(1)), Some(ValueKind::Some));
+}
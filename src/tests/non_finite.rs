@@ -0,0 +1,139 @@
+use super::*;
+use crate::Rejecting;
+use std::cell::RefCell;
+
+macro_rules! policy_serializer {
+    ($name:ident, $policy:ident) => {
+        struct $name;
+
+        impl ser::Serializer for $name {
+            type Error = SerializationError;
+            type Ok = String;
+
+            type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+            impl_serialize!(non_finite = $policy, Ok(format!("{} {}", value_type, v)), floats);
+
+            impl_serialize!(Ok(String::from("none")), none);
+            impl_serialize!(Ok(String::from("unit")), unit);
+            impl_serialize!(Ok(format!("str {}", v)), str);
+
+            impl_serialize!(Err(SerializationError::CannotSerialize), [
+                bool,
+                integers,
+                char, bytes,
+                some,
+                unit_struct, unit_variant,
+                newtype_struct, newtype_variant,
+                seq, map,
+                tuple, tuple_struct, tuple_variant,
+                struct, struct_variant
+            ]);
+        }
+    };
+}
+
+policy_serializer!(Reject, reject);
+policy_serializer!(Null, null);
+policy_serializer!(Unit, unit);
+policy_serializer!(Text, string);
+policy_serializer!(Saturate, saturate);
+
+fn results<S: Serializer<Ok = String, Error = SerializationError>>(serializer: impl Fn() -> S) -> Vec<String> {
+    vec![
+        serializer().serialize_f64(1.5),
+        serializer().serialize_f64(f64::NAN),
+        serializer().serialize_f64(f64::INFINITY),
+        serializer().serialize_f32(f32::NEG_INFINITY),
+    ]
+    .into_iter()
+    .map(|result| result.unwrap_or_else(|error| error.to_string()))
+    .collect()
+}
+
+#[test]
+fn policies() {
+    assert_eq!(
+        results(|| Reject),
+        [
+            "f64 1.5",
+            "Custom(f64 value NaN violates finite)",
+            "Custom(f64 value inf violates finite)",
+            "Custom(f32 value -inf violates finite)",
+        ]
+    );
+    assert_eq!(results(|| Null), ["f64 1.5", "none", "none", "none"]);
+    assert_eq!(results(|| Unit), ["f64 1.5", "unit", "unit", "unit"]);
+    assert_eq!(results(|| Text), ["f64 1.5", "str NaN", "str inf", "str -inf"]);
+    assert_eq!(
+        results(|| Saturate),
+        [
+            "f64 1.5".to_string(),
+            "f64 0".to_string(),
+            format!("f64 {}", f64::MAX),
+            format!("f32 {}", f32::MIN),
+        ]
+    );
+}
+
+#[test]
+fn with_other_options() {
+    struct Logging<'a> {
+        log: &'a RefCell<Vec<String>>,
+    }
+
+    impl<'a> ser::Serializer for Logging<'a> {
+        type Error = SerializationError;
+        type Ok = String;
+
+        type SerializeMap = Rejecting<Self::Ok, Self::Error>;
+        type SerializeSeq = Rejecting<Self::Ok, Self::Error>;
+        type SerializeStruct = Rejecting<Self::Ok, Self::Error>;
+        type SerializeStructVariant = Rejecting<Self::Ok, Self::Error>;
+        type SerializeTuple = Rejecting<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = Rejecting<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = Rejecting<Self::Ok, Self::Error>;
+
+        impl_serialize!(
+            after = |result| {
+                self.log.borrow_mut().push(format!("{}: {:?}", value_type, result));
+                result
+            },
+            non_finite = saturate,
+            validate range(-10.0..=10.0) => Ok(v.to_string()),
+            floats
+        );
+
+        //other kinds ignore the policy
+        impl_serialize!(
+            non_finite = reject,
+            value: Ok(value_type.to_string()),
+            compound: Ok(Rejecting::new(value_type).end_with(value_type.to_string())),
+            [bool, integers, char, str, bytes, none, some, unit, unit_struct, unit_variant]
+        );
+
+        impl_serialize!(
+            value: Ok(value_type.to_string()),
+            compound: Ok(Rejecting::new(value_type).end_with(value_type.to_string())),
+            [newtype_struct, newtype_variant, seq, map, tuple, tuple_struct, tuple_variant, struct, struct_variant]
+        );
+    }
+
+    let log = RefCell::new(Vec::new());
+    let serializer = || Logging { log: &log };
+
+    assert_eq!(serializer().serialize_f32(2.5).unwrap(), "2.5");
+    //saturated before validation
+    assert!(serializer().serialize_f64(f64::INFINITY).is_err());
+    assert_eq!(serializer().serialize_f64(f64::NAN).unwrap(), "0");
+    assert_eq!(serializer().serialize_i32(1).unwrap(), "i32");
+
+    assert_eq!(log.borrow().len(), 3);
+    assert!(log.borrow()[1].starts_with(r#"f64: Err(Custom("f64 value 1.79"#));
+}
@@ -0,0 +1,174 @@
+use crate::{serialize_with_path, Path, Segment, Track, ValueSerializer, WithPath};
+use serde::{ser, Serialize, Serializer};
+use std::{cell::Cell, collections::BTreeMap};
+
+struct Fail;
+
+impl Serialize for Fail {
+    fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(ser::Error::custom("failed"))
+    }
+}
+
+#[derive(Serialize)]
+enum Mode {
+    Limited(Fail),
+    Range(u8, Fail),
+    Named { id: Fail },
+}
+
+fn error_path<T: Serialize>(value: &T) -> String {
+    serialize_with_path(value, ValueSerializer).unwrap_err().path.to_string()
+}
+
+#[test]
+fn root() {
+    let error = serialize_with_path(&Fail, ValueSerializer).unwrap_err();
+
+    assert_eq!(error.path, Path::default());
+    assert_eq!(error.to_string(), ".: failed");
+}
+
+#[test]
+fn sequences() {
+    assert_eq!(error_path(&(1, Fail)), "[1]");
+    assert_eq!(error_path(&vec![vec![], vec![Fail]]), "[1][0]");
+    assert_eq!(error_path(&Some(vec![Fail])), "[0]");
+}
+
+#[test]
+fn map_keys() {
+    let mut map = BTreeMap::new();
+    map.insert("a", vec![]);
+    map.insert("b", vec![Fail]);
+    assert_eq!(error_path(&map), r#"["b"][0]"#);
+
+    let mut map = BTreeMap::new();
+    map.insert(7, Fail);
+    assert_eq!(error_path(&map), "[7]");
+
+    let mut map = BTreeMap::new();
+    map.insert((1, 2), Fail);
+    assert_eq!(error_path(&map), "[?]");
+}
+
+//map with one entry, keys don't need Ord
+struct Entry<K, V>(K, V);
+
+impl<K: Serialize, V: Serialize> Serialize for Entry<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(&self.0, &self.1)?;
+        map.end()
+    }
+}
+
+#[test]
+fn map_keys_serialized_once() {
+    struct Key<'a>(&'a Cell<u32>);
+
+    impl Serialize for Key<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.0.set(self.0.get() + 1);
+            serializer.serialize_str("key")
+        }
+    }
+
+    let calls = Cell::new(0);
+
+    assert_eq!(error_path(&Entry(Key(&calls), Fail)), r#"["key"]"#);
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn wrapped_map_keys() {
+    #[derive(Serialize)]
+    struct Name(&'static str);
+
+    assert_eq!(error_path(&Entry(Name("name"), Fail)), r#"["name"]"#);
+    assert_eq!(error_path(&Entry(Some(3), Fail)), "[3]");
+}
+
+#[test]
+fn failed_key() {
+    assert_eq!(error_path(&Entry(Mode::Named { id: Fail }, 1)), "[?]::Named.id");
+}
+
+#[test]
+fn variants() {
+    assert_eq!(error_path(&Mode::Limited(Fail)), "::Limited");
+    assert_eq!(error_path(&Mode::Range(1, Fail)), "::Range[1]");
+    assert_eq!(error_path(&Mode::Named { id: Fail }), "::Named.id");
+}
+
+#[test]
+fn track() {
+    let track = Track::new();
+    let value = (1, vec![Fail]);
+
+    assert!(value.serialize(WithPath::new(ValueSerializer, &track)).is_err());
+    assert_eq!(
+        track.path().unwrap().segments(),
+        [Segment::Index(1), Segment::Index(0)]
+    );
+
+    //segments of the failed value don't stay behind for the next one
+    track.annotate(());
+    assert!(Some(Fail).serialize(WithPath::new(ValueSerializer, &track)).is_err());
+    assert_eq!(track.path().unwrap(), Path::default());
+}
+
+#[test]
+fn with_path_errors() {
+    let error = (1, vec![Fail]).serialize(WithPath::new(ValueSerializer, &Track::new())).unwrap_err();
+
+    assert_eq!(error.to_string(), "[1][0]: failed");
+}
+
+#[test]
+fn caught_errors() {
+    //fails its first element, then fails by itself
+    struct Catching;
+
+    impl Serialize for Catching {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use ser::SerializeSeq;
+
+            let mut seq = serializer.serialize_seq(Some(2))?;
+            let _ = seq.serialize_element(&Fail);
+            seq.serialize_element(&1)?;
+            Err(ser::Error::custom("caught"))
+        }
+    }
+
+    assert_eq!(
+        serialize_with_path(&(1, Catching), ValueSerializer).unwrap_err().to_string(),
+        "[1]: caught"
+    );
+
+    struct Ignoring;
+
+    impl Serialize for Ignoring {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use ser::SerializeSeq;
+
+            let mut seq = serializer.serialize_seq(Some(1))?;
+            let _ = seq.serialize_element(&Fail);
+            Err(ser::Error::custom("ignored"))
+        }
+    }
+
+    assert_eq!(error_path(&vec![Ignoring]), "[0]");
+}
+
+#[test]
+fn success() {
+    let value = (1, Some("a"), vec![2, 3]);
+
+    assert_eq!(
+        serialize_with_path(&value, ValueSerializer).unwrap(),
+        crate::to_value(&value).unwrap()
+    );
+}
@@ -0,0 +1,231 @@
+use super::*;
+use crate::{check_events, to_events, to_value, Event, RecordingSerializer};
+use serde::Serialize;
+use std::{collections::BTreeMap, panic};
+
+#[derive(Serialize)]
+struct Unit;
+
+#[derive(Serialize)]
+struct Newtype(i8);
+
+#[derive(Serialize)]
+struct Pair(u8, u8);
+
+#[derive(Serialize)]
+enum Enum {
+    Unit,
+    Newtype(char),
+    Tuple(u8, u8),
+    Struct { id: u64 },
+}
+
+#[derive(Serialize)]
+struct Config {
+    name: String,
+    retries: Option<u8>,
+    unit: (Unit, ()),
+    newtype: Newtype,
+    pair: Pair,
+    enums: [Enum; 4],
+    limits: BTreeMap<&'static str, i128>,
+}
+
+fn config() -> Config {
+    Config {
+        name: "config".to_string(),
+        retries: Some(3),
+        unit: (Unit, ()),
+        newtype: Newtype(-1),
+        pair: Pair(1, 2),
+        enums: [Enum::Unit, Enum::Newtype('c'), Enum::Tuple(4, 4), Enum::Struct { id: 5 }],
+        limits: BTreeMap::from([("max", 10)]),
+    }
+}
+
+#[test]
+fn events() {
+    assert_events!(config(), [
+        Event::StructStart { name: "Config", len: 7 },
+        Event::Field("name"),
+        Event::Str("config".to_string()),
+        Event::Field("retries"),
+        Event::Some,
+        Event::U8(3),
+        Event::Field("unit"),
+        Event::TupleStart { len: 2 },
+        Event::UnitStruct { name: "Unit" },
+        Event::Unit,
+        Event::TupleEnd,
+        Event::Field("newtype"),
+        Event::NewtypeStruct { name: "Newtype" },
+        Event::I8(-1),
+        Event::Field("pair"),
+        Event::TupleStructStart { name: "Pair", len: 2 },
+        Event::U8(1),
+        Event::U8(2),
+        Event::TupleStructEnd,
+        Event::Field("enums"),
+        Event::TupleStart { len: 4 },
+        Event::UnitVariant { name: "Enum", variant_index: 0, variant: "Unit" },
+        Event::NewtypeVariant { name: "Enum", variant_index: 1, variant: "Newtype" },
+        Event::Char('c'),
+        Event::TupleVariantStart { name: "Enum", variant_index: 2, variant: "Tuple", len: 2 },
+        Event::U8(4),
+        Event::U8(4),
+        Event::TupleVariantEnd,
+        Event::StructVariantStart { name: "Enum", variant_index: 3, variant: "Struct", len: 1 },
+        Event::Field("id"),
+        Event::U64(5),
+        Event::StructVariantEnd,
+        Event::TupleEnd,
+        Event::Field("limits"),
+        Event::MapStart { len: Some(1) },
+        Event::Key,
+        Event::Str("max".to_string()),
+        Event::I128(10),
+        Event::MapEnd,
+        Event::StructEnd,
+    ]);
+}
+
+#[test]
+fn value_replay() {
+    let value = to_value(&config()).unwrap();
+
+    assert_eq!(to_events(&value).unwrap(), to_events(&config()).unwrap());
+}
+
+#[test]
+fn value_replay_declared_len() {
+    struct Sparse;
+
+    impl Serialize for Sparse {
+        fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use ser::SerializeStruct;
+
+            let mut r#struct = serializer.serialize_struct("Sparse", 3)?;
+            r#struct.serialize_field("id", &1u8)?;
+            r#struct.skip_field("cache")?;
+            r#struct.serialize_field("pair", &Pair(1, 2))?;
+            r#struct.end()
+        }
+    }
+
+    struct Short;
+
+    impl Serialize for Short {
+        fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use ser::SerializeTupleVariant;
+
+            let mut tuple_variant = serializer.serialize_tuple_variant("Short", 0, "Tuple", 4)?;
+            tuple_variant.serialize_field(&Sparse)?;
+            tuple_variant.end()
+        }
+    }
+
+    let value = to_value(&Short).unwrap();
+
+    assert_eq!(to_events(&value).unwrap(), to_events(&Short).unwrap());
+    assert_eq!(
+        to_events(&value).unwrap()[..5],
+        [
+            Event::TupleVariantStart { name: "Short", variant_index: 0, variant: "Tuple", len: 4 },
+            Event::StructStart { name: "Sparse", len: 3 },
+            Event::Field("id"),
+            Event::U8(1),
+            Event::SkipField("cache"),
+        ]
+    );
+}
+
+#[test]
+fn floats() {
+    assert_events!((f32::NAN, f64::NAN), [
+        Event::TupleStart { len: 2 },
+        Event::F32(f32::NAN),
+        Event::F64(f64::NAN),
+        Event::TupleEnd,
+    ]);
+
+    assert_eq!(Event::F64(0.5), Event::F64(0.5));
+    assert_ne!(Event::F64(0.0), Event::F64(-0.0));
+    assert_ne!(Event::F32(1.0), Event::F64(1.0));
+}
+
+#[test]
+fn appends() {
+    let mut events = vec![Event::Unit];
+
+    ser::Serializer::serialize_bytes(RecordingSerializer::new(&mut events), b"b").unwrap();
+    true.serialize(RecordingSerializer::new(&mut events)).unwrap();
+
+    assert_eq!(events, [Event::Unit, Event::Bytes(b"b".to_vec()), Event::Bool(true)]);
+}
+
+fn panic_message(f: impl FnOnce() + panic::UnwindSafe) -> String {
+    let payload = panic::catch_unwind(f).unwrap_err();
+
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload.downcast_ref::<&str>().unwrap().to_string(),
+    }
+}
+
+#[test]
+fn diff() {
+    let message = panic_message(|| {
+        assert_events!((1u8, 2u16, 3u32), [
+            Event::TupleStart { len: 3 },
+            Event::U8(1),
+            Event::U16(5),
+            Event::U32(3),
+            Event::U64(4),
+            Event::TupleEnd,
+        ])
+    });
+
+    assert_eq!(
+        message,
+        "events differ (- expected, + found):\n".to_string()
+            + "      TupleStart { len: 3 }\n"
+            + "      U8(1)\n"
+            + "    - U16(5)\n"
+            + "    + U16(2)\n"
+            + "      U32(3)\n"
+            + "    - U64(4)\n"
+            + "      TupleEnd\n"
+    );
+}
+
+#[test]
+fn large_diff() {
+    let value: Vec<u32> = (0..1100).collect();
+    let mut expected = vec![Event::SeqStart { len: Some(1100) }];
+    expected.extend((0..1100).map(|i| Event::U32(i + 1)));
+    expected.push(Event::SeqEnd);
+
+    //too many differing events for the line diff, only the first mismatch is reported
+    assert_eq!(
+        panic_message(|| check_events(&value, &expected)),
+        "events differ (- expected, + found):\n".to_string()
+            + "      SeqStart { len: Some(1100) }\n"
+            + "    - U32(1)\n"
+            + "    + U32(0)\n"
+            + "    ... 1099 more expected and 1099 more found events differ, too many to diff\n"
+            + "      SeqEnd\n"
+    );
+}
+
+#[test]
+fn failed() {
+    struct Failing;
+
+    impl Serialize for Failing {
+        fn serialize<S: ser::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(ser::Error::custom("failing"))
+        }
+    }
+
+    assert_eq!(panic_message(|| assert_events!(Failing, [])), "serialization failed: failing");
+}
@@ -0,0 +1,197 @@
+use super::*;
+use crate::{Registration, Registry, ValueKind};
+
+#[test]
+fn registrations() {
+    struct CountingSerializer<'a> {
+        count: &'a mut usize,
+    }
+
+    impl_serialize! {
+        impl<'a> ser::Serializer for CountingSerializer<'a> {
+            type Error = SerializationError;
+            type Ok = usize;
+
+            type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+            impl_serialize!(
+                {
+                    *self.count += 1;
+                    Ok(*self.count)
+                },
+                [bool, char]
+            );
+
+            impl_serialize!(Err(SerializationError::CannotSerialize), [
+                bytes,
+                i8, i16, i32, i64, i128,
+                u8, u16, u32, u64, u128,
+                f32, f64,
+                str,
+                none, some, unit,
+                unit_struct, unit_variant,
+                newtype_struct, newtype_variant,
+                seq, map,
+                tuple, tuple_struct, tuple_variant,
+                struct, struct_variant
+            ]);
+
+            #[inline]
+            fn is_human_readable(&self) -> bool {
+                false
+            }
+        }
+    }
+
+    assert_eq!(
+        CountingSerializer::IMPLEMENTED_BY_MACRO[0],
+        Registration {
+            label: "{ *self.count += 1; Ok(*self.count) }",
+            kinds: &[ValueKind::Bool, ValueKind::Char],
+        }
+    );
+
+    assert_eq!(
+        CountingSerializer::IMPLEMENTED_BY_MACRO[1].kinds.len(),
+        28
+    );
+
+    assert_eq!(
+        CountingSerializer::macro_label(ValueKind::TupleVariant),
+        Some("Err(SerializationError::CannotSerialize)")
+    );
+
+    let mut count = 0;
+    assert_eq!(
+        ser::Serializer::serialize_char(CountingSerializer { count: &mut count }, 'c').ok().unwrap(),
+        1
+    );
+    assert!(!ser::Serializer::is_human_readable(&CountingSerializer { count: &mut count }));
+}
+
+#[test]
+fn partial() {
+    struct BoolSerializer;
+
+    impl_serialize! {
+        impl ser::Serializer for BoolSerializer {
+            type Error = SerializationError;
+            type Ok = bool;
+
+            type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+            impl_serialize!(#[inline] Ok(v), bool);
+
+            impl_serialize!(Err(SerializationError::CannotSerialize), [
+                bytes,
+                i8, i16, i32, i64, i128,
+                u8, u16, u32, u64, u128,
+                f32, f64,
+                str,
+                none, some, unit,
+                unit_struct, unit_variant,
+                newtype_struct, newtype_variant,
+                seq, map,
+                tuple, tuple_struct, tuple_variant,
+                struct, struct_variant
+            ]);
+
+            fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+                Err(SerializationError::CannotSerializeFromChar(v))
+            }
+        }
+    }
+
+    assert_eq!(BoolSerializer::macro_label(ValueKind::Bool), Some("Ok(v)"));
+    assert_eq!(BoolSerializer::macro_label(ValueKind::Char), None);
+}
+
+#[test]
+fn generic() {
+    struct DisplaySerializer<S>(core::marker::PhantomData<S>);
+
+    impl_serialize! {
+        impl<S: From<String>> ser::Serializer for DisplaySerializer<S>
+        where
+            S: core::fmt::Debug,
+        {
+            type Error = SerializationError;
+            type Ok = S;
+
+            type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+            type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+            impl_serialize!(Ok(S::from(v.to_string())), [bool, char]);
+
+            fn serialize_some<V>(self, value: &V) -> Result<Self::Ok, Self::Error>
+            where
+                V: ?Sized + ser::Serialize,
+            {
+                value.serialize(self)
+            }
+
+            impl_serialize!(Err(SerializationError::CannotSerialize), [
+                bytes,
+                i8, i16, i32, i64, i128,
+                u8, u16, u32, u64, u128,
+                f32, f64,
+                str,
+                none, unit,
+                unit_struct, unit_variant,
+                newtype_struct, newtype_variant,
+                seq, map,
+                tuple, tuple_struct, tuple_variant,
+                struct, struct_variant
+            ]);
+        }
+    }
+
+    type Strings = DisplaySerializer<String>;
+
+    assert_eq!(Strings::IMPLEMENTED_BY_MACRO.len(), 2);
+    assert_eq!(Strings::macro_label(ValueKind::Char), Some("Ok(S::from(v.to_string()))"));
+    assert_eq!(
+        Strings::macro_label(ValueKind::StructVariant),
+        Some("Err(SerializationError::CannotSerialize)")
+    );
+
+    assert_eq!(Strings::macro_label(ValueKind::Some), None);
+
+    assert_eq!(
+        ser::Serializer::serialize_some(DisplaySerializer::<String>(core::marker::PhantomData), &'c').ok(),
+        Some("c".to_string())
+    );
+}
+
+#[test]
+fn value_kind() {
+    assert_eq!(ValueKind::of(&5u8), Some(ValueKind::U8));
+    assert_eq!(ValueKind::of("str"), Some(ValueKind::Str));
+    assert_eq!(ValueKind::of(&Some(1)), Some(ValueKind::Some));
+    assert_eq!(ValueKind::of(&vec![1, 2]), Some(ValueKind::Seq));
+    assert_eq!(ValueKind::of(&(1, 2)), Some(ValueKind::Tuple));
+
+    for kind in ValueKind::ALL {
+        assert_eq!(ValueKind::from_value_type(kind.as_str()), Some(kind));
+    }
+
+    assert_eq!(ValueKind::from_value_type("string"), None);
+    assert_eq!(ValueKind::StructVariant.to_string(), "struct_variant");
+}
@@ -0,0 +1,123 @@
+use super::*;
+use crate::Rejecting;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[test]
+fn elements() {
+    #[derive(Clone, Copy)]
+    struct CompoundSerializer;
+
+    impl ser::Serializer for CompoundSerializer {
+        type Error = SerializationError;
+        type Ok = ();
+
+        type SerializeMap = Rejecting<Self::Ok, Self::Error>;
+        type SerializeSeq = Rejecting<Self::Ok, Self::Error>;
+        type SerializeStruct = Rejecting<Self::Ok, Self::Error>;
+        type SerializeStructVariant = Rejecting<Self::Ok, Self::Error>;
+        type SerializeTuple = Rejecting<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = Rejecting<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = Rejecting<Self::Ok, Self::Error>;
+
+        impl_serialize!(
+            Ok(Rejecting::with_error(value_type, |value_type| {
+                SerializationError::CannotSerializeFrom(value_type.to_string())
+            })),
+            [
+                seq, map,
+                tuple, tuple_struct, tuple_variant,
+                struct, struct_variant
+            ]
+        );
+
+        impl_serialize!(Err(SerializationError::CannotSerialize), [
+            bool,
+            bytes,
+            i8, i16, i32, i64,
+            u8, u16, u32, u64,
+            f32, f64,
+            char,
+            str,
+            none, some, unit,
+            unit_struct, unit_variant,
+            newtype_struct, newtype_variant
+        ]);
+    }
+
+    assert_eq!(
+        vec!["a", "b"].serialize(CompoundSerializer).err().unwrap(),
+        SerializationError::CannotSerializeFrom("str".to_string())
+    );
+
+    assert_eq!(
+        (Some(1), 2).serialize(CompoundSerializer).err().unwrap(),
+        SerializationError::CannotSerializeFrom("some".to_string())
+    );
+
+    assert_eq!(
+        BTreeMap::from([('k', 1)]).serialize(CompoundSerializer).err().unwrap(),
+        SerializationError::CannotSerializeFrom("char".to_string())
+    );
+
+    assert_eq!(
+        Vec::<u8>::new().serialize(CompoundSerializer).err().unwrap(),
+        SerializationError::CannotSerializeFrom("seq".to_string())
+    );
+
+    assert_eq!(
+        BTreeMap::<u8, u8>::new().serialize(CompoundSerializer).err().unwrap(),
+        SerializationError::CannotSerializeFrom("map".to_string())
+    );
+}
+
+#[test]
+fn end_with() {
+    #[derive(Clone, Copy)]
+    struct EmptySerializer;
+
+    impl ser::Serializer for EmptySerializer {
+        type Error = SerializationError;
+        type Ok = &'static str;
+
+        type SerializeMap = Rejecting<Self::Ok, Self::Error>;
+        type SerializeSeq = Rejecting<Self::Ok, Self::Error>;
+        type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+        impl_serialize!(Ok(Rejecting::new(value_type).end_with(value_type)), [seq, map]);
+
+        impl_serialize!(Err(SerializationError::CannotSerialize), [
+            bool,
+            bytes,
+            i8, i16, i32, i64,
+            u8, u16, u32, u64,
+            f32, f64,
+            char,
+            str,
+            none, some, unit,
+            unit_struct, unit_variant,
+            newtype_struct, newtype_variant,
+            tuple, tuple_struct, tuple_variant,
+            struct, struct_variant
+        ]);
+    }
+
+    assert_eq!(
+        Vec::<u8>::new().serialize(EmptySerializer).ok().unwrap(),
+        "seq"
+    );
+
+    assert_eq!(
+        BTreeMap::<u8, u8>::new().serialize(EmptySerializer).ok().unwrap(),
+        "map"
+    );
+
+    assert_eq!(
+        vec![()].serialize(EmptySerializer).err().unwrap(),
+        SerializationError::Custom("cannot serialize unit".to_string())
+    );
+}
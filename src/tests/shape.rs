@@ -0,0 +1,151 @@
+use crate::{to_shape, Field, Shape, ShapeSerializer, ValueKind};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+enum Mode {
+    Fast,
+    Limited(u32),
+    Range(u8, u8),
+    Named { id: u64 },
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Untagged {
+    Int(u8),
+    Text(&'static str),
+}
+
+#[derive(Serialize)]
+struct Unit;
+
+#[derive(Serialize)]
+struct Newtype(u8);
+
+#[derive(Serialize)]
+struct Pair(u8, &'static str);
+
+#[derive(Serialize)]
+struct Empty {}
+
+#[derive(Serialize)]
+struct Item {
+    name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    port: Option<u16>,
+}
+
+fn shape<T: Serialize>(value: &T) -> String {
+    to_shape(value).unwrap().to_string()
+}
+
+fn merged<T: Serialize>(samples: &[T]) -> Shape {
+    let mut shape = Shape::Unknown;
+    for sample in samples {
+        shape.merge(to_shape(sample).unwrap());
+    }
+    shape
+}
+
+#[test]
+fn primitives() {
+    assert_eq!(to_shape(&1u8).unwrap(), Shape::Primitive(ValueKind::U8));
+    assert_eq!(shape(&"a"), "str");
+    assert_eq!(shape(&()), "()");
+    assert_eq!(shape(&Unit), "Unit");
+    assert_eq!(shape(&Newtype(1)), "Newtype(u8)");
+    assert_eq!(shape(&Pair(1, "a")), "Pair(u8, str)");
+    assert_eq!(shape(&(1u8, 'c')), "(u8, char)");
+    assert_eq!(shape(&Empty {}), "Empty {}");
+    assert_eq!(1u8.serialize(ShapeSerializer).unwrap(), Shape::Primitive(ValueKind::U8));
+}
+
+#[test]
+fn options() {
+    assert_eq!(
+        to_shape(&Some(1u8)).unwrap(),
+        Shape::Option {
+            none: false,
+            some: Some(Box::new(Shape::Primitive(ValueKind::U8))),
+        }
+    );
+    assert_eq!(shape(&None::<u8>), "Option<?>");
+
+    assert_eq!(
+        merged(&[None, Some(1u8)]),
+        Shape::Option {
+            none: true,
+            some: Some(Box::new(Shape::Primitive(ValueKind::U8))),
+        }
+    );
+}
+
+#[test]
+fn sequences_and_maps() {
+    assert_eq!(shape(&Vec::<u8>::new()), "[?]");
+    assert_eq!(shape(&vec![vec![], vec![1u8]]), "[[u8]]");
+    assert_eq!(shape(&vec![Untagged::Int(1), Untagged::Text("a")]), "[u8 | str]");
+
+    let map: BTreeMap<&str, Option<u8>> = [("a", None), ("b", Some(1))].into_iter().collect();
+    assert_eq!(shape(&map), "{str: Option<u8>}");
+}
+
+#[test]
+fn variants() {
+    assert_eq!(shape(&Mode::Fast), "Mode::Fast");
+    assert_eq!(
+        merged(&[Mode::Named { id: 1 }, Mode::Fast, Mode::Range(1, 2), Mode::Limited(3), Mode::Fast]).to_string(),
+        "Mode::Named { id: u64 } | Mode::Fast | Mode::Range(u8, u8) | Mode::Limited(u32)"
+    );
+}
+
+#[test]
+fn optional_fields() {
+    let items = [
+        Item { name: "a", port: None },
+        Item { name: "b", port: Some(80) },
+    ];
+
+    assert_eq!(shape(&items[0]), "Item { name: str, port?: ? }");
+    assert_eq!(
+        merged(&items),
+        Shape::Struct {
+            name: "Item",
+            fields: vec![
+                Field {
+                    name: "name",
+                    shape: Shape::Primitive(ValueKind::Str),
+                    optional: false,
+                },
+                Field {
+                    name: "port",
+                    shape: Shape::Option {
+                        none: false,
+                        some: Some(Box::new(Shape::Primitive(ValueKind::U16))),
+                    },
+                    optional: true,
+                },
+            ],
+        }
+    );
+
+    //elements of one sample are merged too
+    assert_eq!(shape(&&items[..]), "[Item { name: str, port?: Option<u16> }]");
+}
+
+#[test]
+fn one_of() {
+    let mut shape = to_shape(&1u8).unwrap();
+    shape.merge(to_shape("a").unwrap());
+    shape.merge(to_shape(&2u8).unwrap());
+    assert_eq!(shape.to_string(), "u8 | str");
+
+    shape.merge(to_shape(&(1u8, 2u8)).unwrap());
+    shape.merge(to_shape(&(1u8,)).unwrap());
+    assert_eq!(shape.to_string(), "u8 | str | (u8, u8) | (u8)");
+
+    let mut other = to_shape(&'c').unwrap();
+    other.merge(shape);
+    assert_eq!(other.to_string(), "char | u8 | str | (u8, u8) | (u8)");
+}
@@ -0,0 +1,112 @@
+use crate::{estimate_size, Cost, CostModel, SizeEstimator, ValueKind};
+use serde::{ser, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+enum Mode {
+    Fast,
+    Limited(u32),
+    Range(u8, u8),
+    Named { id: u64 },
+}
+
+fn compact<T: Serialize>(value: &T) -> usize {
+    estimate_size(value, &CostModel::compact_binary()).unwrap()
+}
+
+fn json<T: Serialize>(value: &T) -> usize {
+    estimate_size(value, &CostModel::json_ish()).unwrap()
+}
+
+#[test]
+fn varints() {
+    assert_eq!(compact(&0u64), 1);
+    assert_eq!(compact(&127u64), 1);
+    assert_eq!(compact(&128u64), 2);
+    assert_eq!(compact(&u64::MAX), 10);
+    assert_eq!(compact(&-64i32), 1);
+    assert_eq!(compact(&-65i32), 2);
+    assert_eq!(compact(&-1i8), 1);
+    assert_eq!(compact(&1.5f32), 4);
+    assert_eq!(compact(&1.5f64), 8);
+}
+
+#[test]
+fn compact_binary() {
+    assert_eq!(compact(&"abc"), 4);
+    assert_eq!(compact(&Some(5u32)), 2);
+    assert_eq!(compact(&None::<u32>), 1);
+    assert_eq!(compact(&vec![1u16, 2, 300]), 5);
+    assert_eq!(compact(&(1u8, ())), 1);
+
+    assert_eq!(compact(&Mode::Fast), 1);
+    assert_eq!(compact(&Mode::Limited(7)), 2);
+    assert_eq!(compact(&Mode::Range(1, 2)), 3);
+    assert_eq!(compact(&Mode::Named { id: 300 }), 3);
+
+    let map: BTreeMap<_, _> = [("a", 1u8), ("bc", 2)].into_iter().collect();
+    assert_eq!(compact(&map), 8);
+}
+
+#[test]
+fn json_ish() {
+    assert_eq!(json(&true), "true".len());
+    assert_eq!(json(&-300i64), "-300".len());
+    assert_eq!(json(&1.5f64), "1.5".len());
+    assert_eq!(json(&f64::NAN), "null".len());
+    assert_eq!(json(&"abc"), r#""abc""#.len());
+    assert_eq!(json(&Some(5u32)), "5".len());
+    assert_eq!(json(&None::<u32>), "null".len());
+    assert_eq!(json(&vec![1u16, 2, 300]), "[1,2,300]".len());
+    assert_eq!(json(&Vec::<u8>::new()), "[]".len());
+
+    assert_eq!(json(&Mode::Fast), r#""Fast""#.len());
+    assert_eq!(json(&Mode::Limited(7)), r#"{"Limited":7}"#.len());
+    assert_eq!(json(&Mode::Range(1, 2)), r#"{"Range":[1,2]}"#.len());
+    assert_eq!(json(&Mode::Named { id: 300 }), r#"{"Named":{"id":300}}"#.len());
+
+    let map: BTreeMap<_, _> = [("a", 1u8), ("bc", 2)].into_iter().collect();
+    assert_eq!(json(&map), r#"{"a":1,"bc":2}"#.len());
+}
+
+#[test]
+fn bytes() {
+    let compact = CostModel::compact_binary();
+    let json = CostModel::json_ish();
+
+    assert_eq!(SizeEstimator::new(&compact).serialize_bytes(&[1, 20, 255]).unwrap(), 4);
+    assert_eq!(SizeEstimator::new(&json).serialize_bytes(&[1, 20, 255]).unwrap(), "[1,20,255]".len());
+}
+
+#[test]
+fn collect_str() {
+    let model = CostModel::compact_binary();
+
+    assert_eq!(SizeEstimator::new(&model).collect_str(&1234).unwrap(), 5);
+    assert_eq!(compact(&'x'), 1);
+}
+
+#[test]
+fn custom_model() {
+    let model = CostModel::uniform(Cost::Fixed(2)).with(ValueKind::Str, Cost::Varint);
+
+    assert_eq!(model.cost(ValueKind::U64), Cost::Fixed(2));
+    assert_eq!(model.cost(ValueKind::Str), Cost::Varint);
+    assert_eq!(estimate_size(&7u64, &model).unwrap(), 2);
+    assert_eq!(estimate_size(&"ab", &model).unwrap(), 3);
+    assert_eq!(estimate_size(&vec![1u8, 2], &model).unwrap(), 6);
+}
+
+#[test]
+fn errors() {
+    struct Fail;
+
+    impl Serialize for Fail {
+        fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(ser::Error::custom("failed"))
+        }
+    }
+
+    let error = estimate_size(&vec![Fail], &CostModel::json_ish()).unwrap_err();
+    assert_eq!(error.to_string(), "failed");
+}
@@ -0,0 +1,82 @@
+use super::*;
+use crate::{Registry, Stats, ValueKind};
+use serde::Serialize;
+use std::cell::Cell;
+
+struct CountedSerializer<'a> {
+    stats: &'a Stats,
+    before: &'a Cell<u64>,
+}
+
+impl_serialize! {
+    impl<'a> ser::Serializer for CountedSerializer<'a> {
+        type Error = SerializationError;
+        type Ok = ();
+
+        type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+        impl_serialize!(
+            #[inline]
+            counted(self.stats),
+            before = { self.before.set(self.stats.total()); },
+            value: Ok(()),
+            compound: Err(SerializationError::CannotSerializeFrom(value_type.to_string())),
+            all
+        );
+    }
+}
+
+#[test]
+fn counted() {
+    let stats = Stats::new();
+    let before = Cell::new(0);
+    let serializer = || CountedSerializer { stats: &stats, before: &before };
+
+    serializer().serialize_bool(true).unwrap();
+    serializer().serialize_bool(false).unwrap();
+    serializer().serialize_u128(1).unwrap();
+    serializer().serialize_unit_variant("E", 0, "A").unwrap();
+
+    assert_eq!(
+        vec![1].serialize(serializer()).err().unwrap(),
+        SerializationError::CannotSerializeFrom("seq".to_string())
+    );
+
+    assert_eq!(stats.get(ValueKind::Bool), 2);
+    assert_eq!(stats.get(ValueKind::I32), 0);
+    assert_eq!(stats.total(), 5);
+    assert_eq!(before.get(), 5);
+    assert_eq!(
+        stats.iter().collect::<Vec<_>>(),
+        [
+            (ValueKind::Bool, 2),
+            (ValueKind::U128, 1),
+            (ValueKind::UnitVariant, 1),
+            (ValueKind::Seq, 1)
+        ]
+    );
+    assert_eq!(stats.to_string(), "bool: 2\nu128: 1\nunit_variant: 1\nseq: 1\n");
+
+    stats.reset();
+
+    assert_eq!(stats.total(), 0);
+    assert_eq!(stats.to_string(), "");
+}
+
+#[test]
+fn counted_registration() {
+    let registrations = CountedSerializer::IMPLEMENTED_BY_MACRO;
+
+    assert_eq!(registrations.len(), 1);
+    assert_eq!(registrations[0].kinds, ValueKind::ALL);
+    assert_eq!(
+        registrations[0].label,
+        "value: Ok(()), compound: Err(SerializationError::CannotSerializeFrom(value_type.to_string()))"
+    );
+}
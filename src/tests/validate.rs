@@ -0,0 +1,155 @@
+use super::*;
+use crate::{Registry, Stats, ValueKind};
+use std::cell::RefCell;
+
+struct Validating<'a> {
+    log: &'a RefCell<Vec<String>>,
+}
+
+impl_serialize! {
+    impl<'a> ser::Serializer for Validating<'a> {
+        type Error = SerializationError;
+        type Ok = String;
+
+        type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+        impl_serialize!(validate range(-10..=10) => Ok(v.to_string()), [i8, i16, i32, i64, i128]);
+
+        impl_serialize!(validate range(..=10) => Ok(v.to_string()), [u8, u16, u32, u64, u128]);
+
+        impl_serialize!(validate range('a'..='f') => Ok(v.to_string()), char);
+
+        impl_serialize!(
+            after = |result| {
+                self.log.borrow_mut().push(format!("{}: {}", value_type, result.is_ok()));
+                result
+            },
+            validate finite range(0.0..1.0) => Ok(v.to_string()),
+            floats
+        );
+
+        impl_serialize!(validate non_empty max_len(3) => Ok(format!("{:?}", v)), [str, bytes]);
+
+        impl_serialize!(Err(SerializationError::CannotSerialize), [
+            bool,
+            none, some, unit,
+            unit_struct, unit_variant,
+            newtype_struct, newtype_variant,
+            seq, map,
+            tuple, tuple_struct, tuple_variant,
+            struct, struct_variant
+        ]);
+    }
+}
+
+fn custom(message: &str) -> SerializationError {
+    SerializationError::Custom(message.to_string())
+}
+
+#[test]
+fn ranges() {
+    let log = RefCell::new(Vec::new());
+    let serializer = || Validating { log: &log };
+
+    assert_eq!(serializer().serialize_i8(-10).unwrap(), "-10");
+    assert_eq!(serializer().serialize_u128(10).unwrap(), "10");
+    assert_eq!(serializer().serialize_u8(11), Err(custom("u8 value 11 violates range(..=10)")));
+    assert_eq!(serializer().serialize_i64(11), Err(custom("i64 value 11 violates range(-10..=10)")));
+    assert_eq!(serializer().serialize_i128(-11), Err(custom("i128 value -11 violates range(-10..=10)")));
+    assert_eq!(serializer().serialize_f64(0.5).unwrap(), "0.5");
+    assert_eq!(serializer().serialize_f32(1.0), Err(custom("f32 value 1.0 violates range(0.0..1.0)")));
+}
+
+#[test]
+fn char_range() {
+    let log = RefCell::new(Vec::new());
+
+    assert_eq!(Validating { log: &log }.serialize_char('a').unwrap(), "a");
+    assert_eq!(
+        Validating { log: &log }.serialize_char('A'),
+        Err(custom("char value 'A' violates range('a'..='f')"))
+    );
+}
+
+#[test]
+fn lengths() {
+    let log = RefCell::new(Vec::new());
+    let serializer = || Validating { log: &log };
+
+    assert_eq!(serializer().serialize_str("abc").unwrap(), r#""abc""#);
+    assert_eq!(serializer().serialize_str(""), Err(custom(r#"str value "" violates non_empty"#)));
+    assert_eq!(serializer().serialize_str("abcd"), Err(custom(r#"str value "abcd" violates max_len(3)"#)));
+    assert_eq!(serializer().serialize_bytes(&[]), Err(custom("bytes value [] violates non_empty")));
+    assert_eq!(serializer().serialize_bytes(&[1, 2]).unwrap(), "[1, 2]");
+}
+
+#[test]
+fn first_violation_and_hooks() {
+    let log = RefCell::new(Vec::new());
+    let serializer = || Validating { log: &log };
+
+    //finite is checked before range
+    assert_eq!(serializer().serialize_f64(f64::INFINITY), Err(custom("f64 value inf violates finite")));
+    assert_eq!(serializer().serialize_f32(0.25).unwrap(), "0.25");
+    assert_eq!(*log.borrow(), ["f64: false", "f32: true"]);
+}
+
+#[test]
+fn registry() {
+    assert_eq!(Validating::IMPLEMENTED_BY_MACRO.len(), 6);
+    assert_eq!(Validating::IMPLEMENTED_BY_MACRO[1].kinds.len(), 5);
+    assert_eq!(Validating::macro_label(ValueKind::Char), Some("Ok(v.to_string())"));
+    assert_eq!(Validating::macro_label(ValueKind::F32), Some("Ok(v.to_string())"));
+}
+
+#[test]
+fn group_keywords() {
+    struct Counting<'a> {
+        stats: &'a Stats,
+    }
+
+    impl<'a> ser::Serializer for Counting<'a> {
+        type Error = SerializationError;
+        type Ok = ();
+
+        type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+        impl_serialize!(counted(self.stats), validate range(0..) => Ok(()), integers);
+        impl_serialize!(counted(self.stats), Err(SerializationError::CannotSerialize), [char, floats, bool]);
+        impl_serialize!(Err(SerializationError::CannotSerialize), [
+            str, bytes,
+            none, some, unit,
+            unit_struct, unit_variant,
+            newtype_struct, newtype_variant,
+            seq, map,
+            tuple, tuple_struct, tuple_variant,
+            struct, struct_variant
+        ]);
+    }
+
+    let stats = Stats::new();
+    let serializer = || Counting { stats: &stats };
+
+    assert!(serializer().serialize_u8(1).is_ok());
+    assert!(serializer().serialize_i16(-1).is_err());
+    assert!(serializer().serialize_f64(-1.0).is_err());
+    assert!(serializer().serialize_bool(true).is_err());
+    assert!(serializer().serialize_str("").is_err());
+
+    //counted before validation
+    assert_eq!(stats.total(), 4);
+    assert_eq!(stats.get(ValueKind::F64), 1);
+    assert_eq!(stats.get(ValueKind::I16), 1);
+}
@@ -0,0 +1,387 @@
+use super::*;
+use crate::{to_value, Value, ValueKind, ValueSerializer};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+struct Unit;
+
+#[derive(Serialize)]
+struct Newtype(u8);
+
+#[derive(Serialize)]
+struct Pair(u8, char);
+
+#[derive(Serialize)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Serialize)]
+enum Enum {
+    Unit,
+    Newtype(bool),
+    Tuple(u8, u8),
+    Struct { id: u64 },
+}
+
+fn string(v: &str) -> Value {
+    Value::String(v.to_string())
+}
+
+#[test]
+fn values() {
+    assert_eq!(to_value(&true).unwrap(), Value::Bool(true));
+    assert_eq!(to_value(&-1i8).unwrap(), Value::I8(-1));
+    assert_eq!(to_value(&-1i16).unwrap(), Value::I16(-1));
+    assert_eq!(to_value(&-1i32).unwrap(), Value::I32(-1));
+    assert_eq!(to_value(&-1i64).unwrap(), Value::I64(-1));
+    assert_eq!(to_value(&i128::MIN).unwrap(), Value::I128(i128::MIN));
+    assert_eq!(to_value(&1u8).unwrap(), Value::U8(1));
+    assert_eq!(to_value(&1u16).unwrap(), Value::U16(1));
+    assert_eq!(to_value(&1u32).unwrap(), Value::U32(1));
+    assert_eq!(to_value(&1u64).unwrap(), Value::U64(1));
+    assert_eq!(to_value(&u128::MAX).unwrap(), Value::U128(u128::MAX));
+    assert_eq!(to_value(&0.5f32).unwrap(), Value::F32(0.5));
+    assert_eq!(to_value(&0.5f64).unwrap(), Value::F64(0.5));
+    assert_eq!(to_value(&'c').unwrap(), Value::Char('c'));
+    assert_eq!(to_value("str").unwrap(), string("str"));
+    assert_eq!(ValueSerializer.serialize_bytes(b"ab").unwrap(), Value::Bytes(b"ab".to_vec()));
+    assert_eq!(to_value(&None::<u8>).unwrap(), Value::None);
+    assert_eq!(to_value(&Some("str")).unwrap(), Value::Some(Box::new(string("str"))));
+    assert_eq!(to_value(&()).unwrap(), Value::Unit);
+    assert_eq!(to_value(&Unit).unwrap(), Value::UnitStruct { name: "Unit" });
+    assert_eq!(
+        to_value(&Enum::Unit).unwrap(),
+        Value::UnitVariant { name: "Enum", variant_index: 0, variant: "Unit" }
+    );
+    assert_eq!(
+        to_value(&Newtype(1)).unwrap(),
+        Value::NewtypeStruct { name: "Newtype", value: Box::new(Value::U8(1)) }
+    );
+    assert_eq!(
+        to_value(&Enum::Newtype(true)).unwrap(),
+        Value::NewtypeVariant {
+            name: "Enum",
+            variant_index: 1,
+            variant: "Newtype",
+            value: Box::new(Value::Bool(true))
+        }
+    );
+}
+
+#[test]
+fn compounds() {
+    assert_eq!(
+        to_value(&vec![1u8, 2]).unwrap(),
+        Value::Seq { len: Some(2), elements: vec![Value::U8(1), Value::U8(2)] }
+    );
+    assert_eq!(
+        to_value(&(1u8, 'c')).unwrap(),
+        Value::Tuple { len: 2, elements: vec![Value::U8(1), Value::Char('c')] }
+    );
+    assert_eq!(
+        to_value(&Pair(1, 'c')).unwrap(),
+        Value::TupleStruct { name: "Pair", len: 2, fields: vec![Value::U8(1), Value::Char('c')] }
+    );
+    assert_eq!(
+        to_value(&Enum::Tuple(1, 2)).unwrap(),
+        Value::TupleVariant {
+            name: "Enum",
+            variant_index: 2,
+            variant: "Tuple",
+            len: 2,
+            fields: vec![Value::U8(1), Value::U8(2)]
+        }
+    );
+    assert_eq!(
+        to_value(&BTreeMap::from([("a", 1u8)])).unwrap(),
+        Value::Map { len: Some(1), entries: vec![(string("a"), Value::U8(1))] }
+    );
+    assert_eq!(
+        to_value(&Point { x: 1, y: -1 }).unwrap(),
+        Value::Struct {
+            name: "Point",
+            len: 2,
+            fields: vec![("x", Value::I32(1)), ("y", Value::I32(-1))],
+            skipped: vec![]
+        }
+    );
+    assert_eq!(
+        to_value(&Enum::Struct { id: 7 }).unwrap(),
+        Value::StructVariant {
+            name: "Enum",
+            variant_index: 3,
+            variant: "Struct",
+            len: 1,
+            fields: vec![("id", Value::U64(7))],
+            skipped: vec![]
+        }
+    );
+}
+
+#[test]
+fn unknown_len() {
+    struct Counter(u8);
+
+    impl Serialize for Counter {
+        fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(0..self.0)
+        }
+    }
+
+    assert_eq!(
+        to_value(&Counter(2)).unwrap(),
+        Value::Seq { len: Some(2), elements: vec![Value::U8(0), Value::U8(1)] }
+    );
+
+    assert_eq!(
+        ValueSerializer.collect_seq((0..3).filter(|x| x % 2 == 0)).unwrap(),
+        Value::Seq { len: None, elements: vec![Value::I32(0), Value::I32(2)] }
+    );
+}
+
+#[test]
+fn huge_len_hint() {
+    use ser::{SerializeSeq, SerializeStruct};
+
+    let mut seq = ValueSerializer.serialize_seq(Some(usize::MAX)).unwrap();
+    seq.serialize_element(&1u8).unwrap();
+
+    assert_eq!(
+        SerializeSeq::end(seq).unwrap(),
+        Value::Seq { len: Some(usize::MAX), elements: vec![Value::U8(1)] }
+    );
+
+    let mut r#struct = ValueSerializer.serialize_struct("Huge", usize::MAX).unwrap();
+    r#struct.serialize_field("id", &1u8).unwrap();
+
+    assert_eq!(
+        SerializeStruct::end(r#struct).unwrap(),
+        Value::Struct { name: "Huge", len: usize::MAX, fields: vec![("id", Value::U8(1))], skipped: vec![] }
+    );
+}
+
+#[test]
+fn kinds() {
+    assert_eq!(to_value("str").unwrap().kind(), ValueKind::Str);
+    assert_eq!(to_value(&Enum::Struct { id: 7 }).unwrap().kind(), ValueKind::StructVariant);
+}
+
+#[test]
+fn errors() {
+    struct Failing;
+
+    impl Serialize for Failing {
+        fn serialize<S: ser::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(ser::Error::custom("failing"))
+        }
+    }
+
+    assert_eq!(to_value(&vec![Failing]).unwrap_err().to_string(), "failing");
+    assert_eq!(to_value(&Some(Failing)).unwrap_err().to_string(), "failing");
+
+    let mut map = ValueSerializer.serialize_map(None).unwrap();
+
+    ser::SerializeMap::serialize_key(&mut map, "key").unwrap();
+
+    assert_eq!(
+        ser::SerializeMap::end(map).unwrap_err().to_string(),
+        "serialize_value was not called after serialize_key"
+    );
+}
+
+#[derive(Serialize)]
+struct Everything {
+    signed: (i8, i16, i32, i64, i128),
+    unsigned: (u8, u16, u32, u64, u128),
+    values: (bool, f32, f64, char, String),
+    options: (Option<u8>, Option<Option<()>>),
+    unit: ((), Unit),
+    newtype: (Newtype, Enum),
+    enums: Vec<Enum>,
+    pairs: BTreeMap<String, Pair>,
+    point: Point,
+}
+
+fn everything() -> Everything {
+    Everything {
+        signed: (-1, -2, -3, -4, -5),
+        unsigned: (1, 2, 3, 4, 5),
+        values: (true, 0.5, -0.5, 'c', "str".to_string()),
+        options: (None, Some(Some(()))),
+        unit: ((), Unit),
+        newtype: (Newtype(1), Enum::Newtype(false)),
+        enums: vec![Enum::Unit, Enum::Tuple(1, 2), Enum::Struct { id: 3 }],
+        pairs: BTreeMap::from([("a".to_string(), Pair(1, 'a')), ("b".to_string(), Pair(2, 'b'))]),
+        point: Point { x: 1, y: 2 },
+    }
+}
+
+#[test]
+fn replay() {
+    let value = to_value(&everything()).unwrap();
+
+    assert_eq!(value.serialize(ValueSerializer).unwrap(), value);
+    assert_eq!(to_value(&value).unwrap(), value);
+
+    let unknown_len = ValueSerializer.collect_map([(1, 2)].into_iter().filter(|_| true)).unwrap();
+
+    assert_eq!(to_value(&unknown_len).unwrap(), unknown_len);
+}
+
+#[test]
+fn replay_skipped_fields() {
+    #[derive(Serialize)]
+    enum Note {
+        Text {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            title: Option<String>,
+            body: &'static str,
+        },
+    }
+
+    #[derive(Serialize)]
+    struct Entry {
+        id: u8,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        note: Option<Note>,
+        count: u8,
+    }
+
+    let value = to_value(&Entry { id: 1, note: None, count: 2 }).unwrap();
+
+    assert_eq!(
+        value,
+        Value::Struct {
+            name: "Entry",
+            len: 2,
+            fields: vec![("id", Value::U8(1)), ("count", Value::U8(2))],
+            skipped: vec![(1, "note")]
+        }
+    );
+    assert_eq!(to_value(&value).unwrap(), value);
+
+    let value = to_value(&Note::Text { title: None, body: "text" }).unwrap();
+
+    assert_eq!(
+        value,
+        Value::StructVariant {
+            name: "Note",
+            variant_index: 0,
+            variant: "Text",
+            len: 1,
+            fields: vec![("body", string("text"))],
+            skipped: vec![(0, "title")]
+        }
+    );
+    assert_eq!(to_value(&value).unwrap(), value);
+}
+
+#[test]
+fn replay_arguments() {
+    use crate::{Handler, ValueRef};
+
+    struct ArgumentsHandler;
+
+    impl Handler for ArgumentsHandler {
+        type Ok = String;
+        type Error = SerializationError;
+
+        fn handle(self, _kind: ValueKind, value: ValueRef<'_>) -> Result<String, SerializationError> {
+            Ok(format!("{:?}", value))
+        }
+    }
+
+    let values = [
+        to_value(&Enum::Unit).unwrap(),
+        to_value(&Newtype(1)).unwrap(),
+        to_value(&Enum::Newtype(true)).unwrap(),
+        ValueSerializer.serialize_seq(None).map(ser::SerializeSeq::end).unwrap().unwrap(),
+        ValueSerializer.serialize_tuple_variant("E", 4, "V", 0).map(ser::SerializeTupleVariant::end).unwrap().unwrap(),
+        ValueSerializer.serialize_struct("S", 0).map(ser::SerializeStruct::end).unwrap().unwrap(),
+    ];
+
+    let replayed: Vec<_> = values.iter().map(|value| value.serialize(ArgumentsHandler.serializer()).unwrap()).collect();
+
+    assert_eq!(
+        replayed,
+        [
+            "UnitVariant { name: \"Enum\", variant_index: 0, variant: \"Unit\" }",
+            "NewtypeStruct { name: \"Newtype\", value: Erased(Some(U8)) }",
+            "NewtypeVariant { name: \"Enum\", variant_index: 1, variant: \"Newtype\", value: Erased(Some(Bool)) }",
+            "Seq { len: None }",
+            "TupleVariant { name: \"E\", variant_index: 4, variant: \"V\", len: 0 }",
+            "Struct { name: \"S\", len: 0 }",
+        ]
+    );
+}
+
+#[test]
+fn erased() {
+    use crate::{Handler, ValueRef};
+
+    struct InnerHandler;
+
+    impl Handler for InnerHandler {
+        type Ok = Value;
+        type Error = crate::ValueError;
+
+        fn handle(self, _kind: ValueKind, value: ValueRef<'_>) -> Result<Value, Self::Error> {
+            match value {
+                ValueRef::Some(value) | ValueRef::NewtypeStruct { value, .. } => value.serialize(ValueSerializer),
+                _ => Value::try_from(value),
+            }
+        }
+    }
+
+    assert_eq!(Some(vec![1u8]).serialize(InnerHandler.serializer()).unwrap(), to_value(&vec![1u8]).unwrap());
+    assert_eq!(Newtype(4).serialize(InnerHandler.serializer()).unwrap(), Value::U8(4));
+    assert_eq!(None::<u8>.serialize(InnerHandler.serializer()).unwrap(), Value::None);
+
+    //every method and compound is replayed through the erased value
+    assert_eq!(Some(everything()).serialize(InnerHandler.serializer()).unwrap(), to_value(&everything()).unwrap());
+    assert_eq!(
+        Some(BTreeMap::from([("a", 'c')])).serialize(InnerHandler.serializer()).unwrap(),
+        to_value(&BTreeMap::from([("a", 'c')])).unwrap()
+    );
+    assert_eq!(
+        Some(format_args!("{}-{}", 1, 2)).serialize(InnerHandler.serializer()).unwrap(),
+        Value::String("1-2".to_string())
+    );
+
+    //custom errors of the value keep their message
+    struct Fail;
+
+    impl Serialize for Fail {
+        fn serialize<S: ser::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(ser::Error::custom("failed"))
+        }
+    }
+
+    assert_eq!(
+        Some(vec![Fail]).serialize(InnerHandler.serializer()).unwrap_err().to_string(),
+        "failed"
+    );
+
+    //errors of the serializer are returned as they are
+    assert_eq!(
+        crate::Erased::new(&&vec![1u8]).serialize(crate::MapKeySerializer::new()).unwrap_err().to_string(),
+        "cannot use seq as map key"
+    );
+
+    //calls after a swallowed error of the serializer return the stored error
+    struct Swallow;
+
+    impl Serialize for Swallow {
+        fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(2))?;
+            let _ = ser::SerializeSeq::serialize_element(&mut seq, &Fail);
+            let _ = ser::SerializeSeq::serialize_element(&mut seq, &1u8);
+            ser::SerializeSeq::end(seq)
+        }
+    }
+
+    assert_eq!(crate::Erased::new(&&Swallow).serialize(ValueSerializer).unwrap_err().to_string(), "failed");
+}
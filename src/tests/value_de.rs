@@ -0,0 +1,203 @@
+use crate::{from_value, to_value, Value};
+use serde::{de::IntoDeserializer, Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Unit;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Newtype(i16);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Pair(u8, char);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Enum {
+    Unit,
+    Newtype(Option<u8>),
+    Tuple(u8, u8),
+    Struct { id: u64 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Everything<'a> {
+    flag: bool,
+    numbers: (i8, i32, i64, i128, u16, u64, u128, f32, f64),
+    letter: char,
+    text: String,
+    borrowed: &'a str,
+    #[serde(with = "bytes")]
+    bytes: Vec<u8>,
+    options: (Option<u8>, Option<Option<()>>),
+    units: ((), Unit),
+    newtype: Newtype,
+    pair: Pair,
+    enums: Vec<Enum>,
+    map: BTreeMap<String, Vec<u8>>,
+}
+
+mod bytes {
+    use serde::{de, Deserializer, Serializer};
+    use std::fmt;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(bytes)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> de::Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("bytes")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+                Ok(v.to_vec())
+            }
+        }
+
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+fn everything() -> Everything<'static> {
+    Everything {
+        flag: true,
+        numbers: (-1, -2, -3, -4, 5, 6, 7, 0.5, -0.25),
+        letter: 'c',
+        text: "text".to_string(),
+        borrowed: "borrowed",
+        bytes: vec![1, 2],
+        options: (None, Some(Some(()))),
+        units: ((), Unit),
+        newtype: Newtype(-16),
+        pair: Pair(1, 'p'),
+        enums: vec![Enum::Unit, Enum::Newtype(Some(1)), Enum::Newtype(None), Enum::Tuple(1, 2), Enum::Struct { id: 3 }],
+        map: BTreeMap::from([("a".to_string(), vec![1]), ("b".to_string(), vec![])]),
+    }
+}
+
+#[test]
+fn round_trip() {
+    let value = to_value(&everything()).unwrap();
+
+    assert_eq!(from_value::<Everything>(&value).unwrap(), everything());
+}
+
+#[test]
+fn owned() {
+    type Owned = (String, Vec<Enum>, BTreeMap<String, Vec<u8>>, Option<Newtype>, Pair, Unit);
+
+    let owned = || -> Owned {
+        (
+            "text".to_string(),
+            vec![Enum::Unit, Enum::Newtype(Some(1)), Enum::Tuple(1, 2), Enum::Struct { id: 3 }],
+            BTreeMap::from([("a".to_string(), vec![1])]),
+            Some(Newtype(-16)),
+            Pair(1, 'p'),
+            Unit,
+        )
+    };
+
+    assert_eq!(Owned::deserialize(to_value(&owned()).unwrap()).unwrap(), owned());
+    assert_eq!(Enum::deserialize(Value::String("Unit".to_string()).into_deserializer()).unwrap(), Enum::Unit);
+
+    //strings can't be borrowed from owned value
+    assert_eq!(
+        Everything::deserialize(to_value(&everything()).unwrap()).unwrap_err().to_string(),
+        "invalid type: string \"borrowed\", expected a borrowed string"
+    );
+    assert_eq!(
+        <(u8, u8)>::deserialize(to_value(&(1u8, 2u8, 3u8)).unwrap()).unwrap_err().to_string(),
+        "invalid length 3, expected 2 elements"
+    );
+}
+
+#[test]
+fn borrowed() {
+    let value = Value::String("borrowed".to_string());
+    let borrowed: &str = from_value(&value).unwrap();
+
+    assert!(std::ptr::eq(borrowed, value_str(&value)));
+}
+
+fn value_str(value: &Value) -> &str {
+    match value {
+        Value::String(v) => v,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn any() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum Untagged {
+        Number(u8),
+        Text(String),
+        List(Vec<Untagged>),
+    }
+
+    let value = to_value(&(1u8, "a", vec![2u8])).unwrap();
+
+    assert_eq!(
+        from_value::<Untagged>(&value).unwrap(),
+        Untagged::List(vec![
+            Untagged::Number(1),
+            Untagged::Text("a".to_string()),
+            Untagged::List(vec![Untagged::Number(2)])
+        ])
+    );
+}
+
+#[test]
+fn enums() {
+    assert_eq!(from_value::<Enum>(&Value::String("Unit".to_string())).unwrap(), Enum::Unit);
+
+    assert_eq!(
+        Enum::deserialize((&to_value(&Enum::Struct { id: 1 }).unwrap()).into_deserializer()).unwrap(),
+        Enum::Struct { id: 1 }
+    );
+
+    assert_eq!(
+        from_value::<Enum>(&Value::Bool(true)).unwrap_err().to_string(),
+        "invalid type: boolean `true`, expected enum"
+    );
+
+    assert_eq!(
+        from_value::<Enum>(&to_value(&Enum::Unit).unwrap()).unwrap(),
+        Enum::Unit
+    );
+
+    let tuple_as_unit = Value::TupleVariant { name: "Enum", variant_index: 0, variant: "Unit", len: 0, fields: vec![] };
+
+    assert_eq!(
+        from_value::<Enum>(&tuple_as_unit).unwrap_err().to_string(),
+        "invalid type: tuple variant, expected unit variant"
+    );
+}
+
+#[test]
+fn options() {
+    assert_eq!(from_value::<Option<u8>>(&Value::U8(1)).unwrap(), Some(1));
+    assert_eq!(from_value::<Option<u8>>(&Value::None).unwrap(), None);
+    assert_eq!(from_value::<Option<u8>>(&Value::Some(Box::new(Value::U8(2)))).unwrap(), Some(2));
+}
+
+#[test]
+fn lengths() {
+    let value = to_value(&(1u8, 2u8, 3u8)).unwrap();
+
+    assert_eq!(
+        from_value::<(u8, u8)>(&value).unwrap_err().to_string(),
+        "invalid length 3, expected 2 elements"
+    );
+
+    assert_eq!(
+        from_value::<Pair>(&to_value(&(1u8,)).unwrap()).unwrap_err().to_string(),
+        "invalid length 1, expected tuple struct Pair with 2 elements"
+    );
+}
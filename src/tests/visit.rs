@@ -0,0 +1,245 @@
+use super::*;
+use serde::de::{self, Deserializer, IntoDeserializer, Visitor};
+
+type ValueError = de::value::Error;
+
+#[test]
+fn all_value_type() {
+    struct NothingVisitor;
+
+    impl<'de> Visitor<'de> for NothingVisitor {
+        type Value = ();
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("nothing")
+        }
+
+        impl_visit!(Err(de::Error::custom(value_type)), [
+            bool,
+            i8, i16, i32, i64, i128,
+            u8, u16, u32, u64, u128,
+            f32, f64,
+            char,
+            str, borrowed_str, string,
+            bytes, borrowed_bytes, byte_buf,
+            none, some, unit,
+            newtype_struct,
+            seq, map, enum
+        ]);
+    }
+
+    assert_eq!(
+        NothingVisitor.visit_bool::<ValueError>(true).err().unwrap().to_string(),
+        "bool"
+    );
+
+    assert_eq!(
+        NothingVisitor.visit_i128::<ValueError>(4).err().unwrap().to_string(),
+        "i128"
+    );
+
+    assert_eq!(
+        NothingVisitor.visit_u128::<ValueError>(4).err().unwrap().to_string(),
+        "u128"
+    );
+
+    assert_eq!(
+        NothingVisitor.visit_borrowed_str::<ValueError>("str").err().unwrap().to_string(),
+        "borrowed_str"
+    );
+
+    assert_eq!(
+        NothingVisitor.visit_string::<ValueError>("string".to_string()).err().unwrap().to_string(),
+        "string"
+    );
+
+    assert_eq!(
+        NothingVisitor.visit_borrowed_bytes::<ValueError>(&[1, 2]).err().unwrap().to_string(),
+        "borrowed_bytes"
+    );
+
+    assert_eq!(
+        NothingVisitor.visit_none::<ValueError>().err().unwrap().to_string(),
+        "none"
+    );
+
+    assert_eq!(
+        NothingVisitor
+            .visit_some(4u8.into_deserializer() as de::value::U8Deserializer<ValueError>)
+            .err()
+            .unwrap()
+            .to_string(),
+        "some"
+    );
+
+    assert_eq!(
+        NothingVisitor
+            .visit_seq(de::value::SeqDeserializer::<_, ValueError>::new(vec![1u8].into_iter()))
+            .err()
+            .unwrap()
+            .to_string(),
+        "seq"
+    );
+
+    assert_eq!(
+        NothingVisitor
+            .visit_map(de::value::MapDeserializer::<_, ValueError>::new(vec![(1u8, 2u8)].into_iter()))
+            .err()
+            .unwrap()
+            .to_string(),
+        "map"
+    );
+}
+
+#[test]
+fn borrowed() {
+    struct BorrowedStrVisitor;
+
+    impl<'de> Visitor<'de> for BorrowedStrVisitor {
+        type Value = &'de str;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("borrowed string")
+        }
+
+        impl_visit!(Ok(v), borrowed_str);
+
+        impl_visit!(
+            core::str::from_utf8(v).map_err(de::Error::custom),
+            borrowed_bytes
+        );
+
+        impl_visit!(Err(de::Error::custom(value_type)), [str, string]);
+    }
+
+    let deserializer: de::value::BorrowedStrDeserializer<ValueError> =
+        de::value::BorrowedStrDeserializer::new("borrowed");
+    assert_eq!(
+        deserializer.deserialize_any(BorrowedStrVisitor).ok().unwrap(),
+        "borrowed"
+    );
+
+    let deserializer: de::value::BorrowedBytesDeserializer<ValueError> =
+        de::value::BorrowedBytesDeserializer::new(b"bytes");
+    assert_eq!(
+        deserializer.deserialize_any(BorrowedStrVisitor).ok().unwrap(),
+        "bytes"
+    );
+
+    let deserializer: de::value::StringDeserializer<ValueError> =
+        "owned".to_string().into_deserializer();
+    assert_eq!(
+        deserializer.deserialize_any(BorrowedStrVisitor).err().unwrap().to_string(),
+        "string"
+    );
+}
+
+#[test]
+fn nested() {
+    struct SumVisitor;
+
+    impl<'de> Visitor<'de> for SumVisitor {
+        type Value = u64;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("numbers")
+        }
+
+        impl_visit!(Ok(v as u64), [u8, u16, u32, u64]);
+
+        impl_visit!(Ok(0), [none, unit]);
+
+        impl_visit!(deserializer.deserialize_any(SumVisitor), [some, newtype_struct]);
+
+        impl_visit!(
+            {
+                let mut seq = seq;
+                let mut sum = 0;
+                while let Some(v) = seq.next_element::<u64>()? {
+                    sum += v;
+                }
+                Ok(sum)
+            },
+            seq
+        );
+    }
+
+    let deserializer: de::value::SeqDeserializer<_, ValueError> =
+        de::value::SeqDeserializer::new(vec![1u64, 2, 3].into_iter());
+    assert_eq!(
+        deserializer.deserialize_any(SumVisitor).ok().unwrap(),
+        6
+    );
+
+    let deserializer: de::value::U32Deserializer<ValueError> = 7u32.into_deserializer();
+    assert_eq!(
+        SumVisitor.visit_some(deserializer).ok().unwrap(),
+        7
+    );
+
+    assert_eq!(
+        SumVisitor.visit_unit::<ValueError>().ok().unwrap(),
+        0
+    );
+}
+
+#[test]
+fn unexpected() {
+    struct UnitVisitor;
+
+    impl<'de> Visitor<'de> for UnitVisitor {
+        type Value = ();
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("unit")
+        }
+
+        impl_visit!(Ok(()), unit);
+
+        impl_visit!(Err(de::Error::invalid_type(unexpected, &self)), [
+            bool,
+            i8, i16, i32, i64, i128,
+            u8, u16, u32, u64, u128,
+            f32, f64,
+            char,
+            str, borrowed_str, string,
+            bytes, borrowed_bytes, byte_buf,
+            none, some,
+            newtype_struct,
+            seq, map, enum
+        ]);
+    }
+
+    assert_eq!(
+        UnitVisitor.visit_i8::<ValueError>(-3).err().unwrap().to_string(),
+        "invalid type: integer `-3`, expected unit"
+    );
+
+    assert_eq!(
+        UnitVisitor.visit_u128::<ValueError>(3).err().unwrap().to_string(),
+        "invalid type: u128, expected unit"
+    );
+
+    assert_eq!(
+        UnitVisitor.visit_string::<ValueError>("string".to_string()).err().unwrap().to_string(),
+        "invalid type: string \"string\", expected unit"
+    );
+
+    assert_eq!(
+        UnitVisitor.visit_byte_buf::<ValueError>(vec![1]).err().unwrap().to_string(),
+        "invalid type: byte array, expected unit"
+    );
+
+    let deserializer: de::value::SeqDeserializer<_, ValueError> =
+        de::value::SeqDeserializer::new(vec![1u8].into_iter());
+    assert_eq!(
+        deserializer.deserialize_any(UnitVisitor).err().unwrap().to_string(),
+        "invalid type: sequence, expected unit"
+    );
+
+    let deserializer: de::value::UnitDeserializer<ValueError> = ().into_deserializer();
+    assert_eq!(
+        deserializer.deserialize_any(UnitVisitor).ok(),
+        Some(())
+    );
+}
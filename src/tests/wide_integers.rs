@@ -0,0 +1,54 @@
+use super::*;
+
+
+#[test]
+fn wide_integers() {
+    #[derive(Clone, Copy)]
+    struct WideSerializer;
+
+    impl ser::Serializer for WideSerializer {
+        type Error = SerializationError;
+        type Ok = String;
+
+        type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+        impl_serialize!(Ok(format!("{value_type} {v}")), [i128, u128]);
+
+        impl_serialize!(Err(SerializationError::CannotSerializeFrom(value_type.to_string())), [
+            bool,
+            bytes,
+            i8, i16, i32, i64,
+            u8, u16, u32, u64,
+            f32, f64,
+            char,
+            str,
+            none, some, unit,
+            unit_struct, unit_variant,
+            newtype_struct, newtype_variant,
+            seq, map,
+            tuple, tuple_struct, tuple_variant,
+            struct, struct_variant
+        ]);
+    }
+
+    assert_eq!(
+        WideSerializer.serialize_i128(i128::MIN).unwrap(),
+        format!("i128 {}", i128::MIN)
+    );
+
+    assert_eq!(
+        WideSerializer.serialize_u128(u128::MAX).unwrap(),
+        format!("u128 {}", u128::MAX)
+    );
+
+    assert_eq!(
+        WideSerializer.serialize_i64(1).err().unwrap(),
+        SerializationError::CannotSerializeFrom("i64".to_string())
+    );
+}
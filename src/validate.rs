@@ -0,0 +1,14 @@
+use core::fmt;
+use serde::ser;
+
+/// Error of a value what violates a constraint of `validate` option, see [metavariables](index.html#validate).
+///
+/// The message is `"{value_type} value {value:?} violates {constraint}"`,
+/// like `i32 value 150 violates range(1..=100)`.
+pub fn violation<E, T>(value_type: &str, value: &T, constraint: &str) -> E
+where
+    E: ser::Error,
+    T: ?Sized + fmt::Debug,
+{
+    E::custom(format_args!("{} value {:?} violates {}", value_type, value, constraint))
+}
@@ -0,0 +1,499 @@
+use crate::{ValueKind, ValueRef, ValueSerializer};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+use serde::{
+    de,
+    ser::{self, Serialize},
+};
+
+/// Owned value of the serde data model, one variant per serialize method.
+///
+/// Built by [ValueSerializer] or [to_value]. Names, variant indexes and lengths passed to
+/// the serializer are kept as they were. Fields passed to `skip_field` are kept in `skipped`
+/// with the number of `fields` serialized before them.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// `serialize_bool`
+    Bool(bool),
+    /// `serialize_i8`
+    I8(i8),
+    /// `serialize_i16`
+    I16(i16),
+    /// `serialize_i32`
+    I32(i32),
+    /// `serialize_i64`
+    I64(i64),
+    /// `serialize_i128`
+    I128(i128),
+    /// `serialize_u8`
+    U8(u8),
+    /// `serialize_u16`
+    U16(u16),
+    /// `serialize_u32`
+    U32(u32),
+    /// `serialize_u64`
+    U64(u64),
+    /// `serialize_u128`
+    U128(u128),
+    /// `serialize_f32`
+    F32(f32),
+    /// `serialize_f64`
+    F64(f64),
+    /// `serialize_char`
+    Char(char),
+    /// `serialize_str`
+    String(String),
+    /// `serialize_bytes`
+    Bytes(Vec<u8>),
+    /// `serialize_none`
+    None,
+    /// `serialize_some`
+    Some(Box<Value>),
+    /// `serialize_unit`
+    Unit,
+    /// `serialize_unit_struct`
+    UnitStruct {
+        name: &'static str,
+    },
+    /// `serialize_unit_variant`
+    UnitVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    },
+    /// `serialize_newtype_struct`
+    NewtypeStruct {
+        name: &'static str,
+        value: Box<Value>,
+    },
+    /// `serialize_newtype_variant`
+    NewtypeVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: Box<Value>,
+    },
+    /// `serialize_seq`
+    Seq {
+        len: Option<usize>,
+        elements: Vec<Value>,
+    },
+    /// `serialize_tuple`
+    Tuple {
+        len: usize,
+        elements: Vec<Value>,
+    },
+    /// `serialize_tuple_struct`
+    TupleStruct {
+        name: &'static str,
+        len: usize,
+        fields: Vec<Value>,
+    },
+    /// `serialize_tuple_variant`
+    TupleVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+        fields: Vec<Value>,
+    },
+    /// `serialize_map`
+    Map {
+        len: Option<usize>,
+        entries: Vec<(Value, Value)>,
+    },
+    /// `serialize_struct`
+    Struct {
+        name: &'static str,
+        len: usize,
+        fields: Vec<(&'static str, Value)>,
+        skipped: Vec<(usize, &'static str)>,
+    },
+    /// `serialize_struct_variant`
+    StructVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+        fields: Vec<(&'static str, Value)>,
+        skipped: Vec<(usize, &'static str)>,
+    },
+}
+
+impl Value {
+    /// Returns kind of the method what produced the value.
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Value::Bool(_) => ValueKind::Bool,
+            Value::I8(_) => ValueKind::I8,
+            Value::I16(_) => ValueKind::I16,
+            Value::I32(_) => ValueKind::I32,
+            Value::I64(_) => ValueKind::I64,
+            Value::I128(_) => ValueKind::I128,
+            Value::U8(_) => ValueKind::U8,
+            Value::U16(_) => ValueKind::U16,
+            Value::U32(_) => ValueKind::U32,
+            Value::U64(_) => ValueKind::U64,
+            Value::U128(_) => ValueKind::U128,
+            Value::F32(_) => ValueKind::F32,
+            Value::F64(_) => ValueKind::F64,
+            Value::Char(_) => ValueKind::Char,
+            Value::String(_) => ValueKind::Str,
+            Value::Bytes(_) => ValueKind::Bytes,
+            Value::None => ValueKind::None,
+            Value::Some(_) => ValueKind::Some,
+            Value::Unit => ValueKind::Unit,
+            Value::UnitStruct { .. } => ValueKind::UnitStruct,
+            Value::UnitVariant { .. } => ValueKind::UnitVariant,
+            Value::NewtypeStruct { .. } => ValueKind::NewtypeStruct,
+            Value::NewtypeVariant { .. } => ValueKind::NewtypeVariant,
+            Value::Seq { .. } => ValueKind::Seq,
+            Value::Tuple { .. } => ValueKind::Tuple,
+            Value::TupleStruct { .. } => ValueKind::TupleStruct,
+            Value::TupleVariant { .. } => ValueKind::TupleVariant,
+            Value::Map { .. } => ValueKind::Map,
+            Value::Struct { .. } => ValueKind::Struct,
+            Value::StructVariant { .. } => ValueKind::StructVariant,
+        }
+    }
+
+    /// Returns the value as [`de::Unexpected`] for errors of deserialization.
+    pub fn unexpected(&self) -> de::Unexpected<'_> {
+        match self {
+            Value::Bool(v) => de::Unexpected::Bool(*v),
+            Value::I8(v) => de::Unexpected::Signed(*v as i64),
+            Value::I16(v) => de::Unexpected::Signed(*v as i64),
+            Value::I32(v) => de::Unexpected::Signed(*v as i64),
+            Value::I64(v) => de::Unexpected::Signed(*v),
+            Value::I128(_) => de::Unexpected::Other("i128"),
+            Value::U8(v) => de::Unexpected::Unsigned(*v as u64),
+            Value::U16(v) => de::Unexpected::Unsigned(*v as u64),
+            Value::U32(v) => de::Unexpected::Unsigned(*v as u64),
+            Value::U64(v) => de::Unexpected::Unsigned(*v),
+            Value::U128(_) => de::Unexpected::Other("u128"),
+            Value::F32(v) => de::Unexpected::Float(*v as f64),
+            Value::F64(v) => de::Unexpected::Float(*v),
+            Value::Char(v) => de::Unexpected::Char(*v),
+            Value::String(v) => de::Unexpected::Str(v),
+            Value::Bytes(v) => de::Unexpected::Bytes(v),
+            Value::None | Value::Some(_) => de::Unexpected::Option,
+            Value::Unit | Value::UnitStruct { .. } => de::Unexpected::Unit,
+            Value::UnitVariant { .. } => de::Unexpected::UnitVariant,
+            Value::NewtypeStruct { .. } => de::Unexpected::NewtypeStruct,
+            Value::NewtypeVariant { .. } => de::Unexpected::NewtypeVariant,
+            Value::Seq { .. } | Value::Tuple { .. } | Value::TupleStruct { .. } => de::Unexpected::Seq,
+            Value::TupleVariant { .. } => de::Unexpected::TupleVariant,
+            Value::Map { .. } | Value::Struct { .. } => de::Unexpected::Map,
+            Value::StructVariant { .. } => de::Unexpected::StructVariant,
+        }
+    }
+}
+
+/// Rust-like notation: `Some("text")`, `[1, 2]`, `Point { x: 1, y: 2 }`, `Mode::Limited(3)`.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Bool(v) => write!(f, "{}", v),
+            Value::I8(v) => write!(f, "{}", v),
+            Value::I16(v) => write!(f, "{}", v),
+            Value::I32(v) => write!(f, "{}", v),
+            Value::I64(v) => write!(f, "{}", v),
+            Value::I128(v) => write!(f, "{}", v),
+            Value::U8(v) => write!(f, "{}", v),
+            Value::U16(v) => write!(f, "{}", v),
+            Value::U32(v) => write!(f, "{}", v),
+            Value::U64(v) => write!(f, "{}", v),
+            Value::U128(v) => write!(f, "{}", v),
+            Value::F32(v) => write!(f, "{:?}", v),
+            Value::F64(v) => write!(f, "{:?}", v),
+            Value::Char(v) => write!(f, "{:?}", v),
+            Value::String(v) => write!(f, "{:?}", v),
+            Value::Bytes(v) => write!(f, "b{:?}", v),
+            Value::None => f.write_str("None"),
+            Value::Some(value) => write!(f, "Some({})", value),
+            Value::Unit => f.write_str("()"),
+            Value::UnitStruct { name } => f.write_str(name),
+            Value::UnitVariant { name, variant, .. } => write!(f, "{}::{}", name, variant),
+            Value::NewtypeStruct { name, value } => write!(f, "{}({})", name, value),
+            Value::NewtypeVariant { name, variant, value, .. } => write!(f, "{}::{}({})", name, variant, value),
+            Value::Seq { elements, .. } => write_list(f, "[", elements, "]"),
+            Value::Tuple { elements, .. } => write_list(f, "(", elements, ")"),
+            Value::TupleStruct { name, fields, .. } => {
+                f.write_str(name)?;
+                write_list(f, "(", fields, ")")
+            }
+            Value::TupleVariant { name, variant, fields, .. } => {
+                write!(f, "{}::{}", name, variant)?;
+                write_list(f, "(", fields, ")")
+            }
+            Value::Map { entries, .. } => {
+                f.write_str("{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { ", " };
+                    write!(f, "{}{}: {}", separator, key, value)?;
+                }
+                f.write_str("}")
+            }
+            Value::Struct { name, fields, .. } => {
+                f.write_str(name)?;
+                write_fields(f, fields)
+            }
+            Value::StructVariant { name, variant, fields, .. } => {
+                write!(f, "{}::{}", name, variant)?;
+                write_fields(f, fields)
+            }
+        }
+    }
+}
+
+fn write_list(f: &mut fmt::Formatter, open: &str, elements: &[Value], close: &str) -> fmt::Result {
+    f.write_str(open)?;
+    for (i, element) in elements.iter().enumerate() {
+        let separator = if i == 0 { "" } else { ", " };
+        write!(f, "{}{}", separator, element)?;
+    }
+    f.write_str(close)
+}
+
+fn write_fields(f: &mut fmt::Formatter, fields: &[(&'static str, Value)]) -> fmt::Result {
+    if fields.is_empty() {
+        return f.write_str(" {}");
+    }
+
+    f.write_str(" { ")?;
+    for (i, (key, value)) in fields.iter().enumerate() {
+        let separator = if i == 0 { "" } else { ", " };
+        write!(f, "{}{}: {}", separator, key, value)?;
+    }
+    f.write_str(" }")
+}
+
+/// Copies the arguments, compound values start empty.
+impl TryFrom<ValueRef<'_>> for Value {
+    type Error = ValueError;
+
+    fn try_from(value: ValueRef<'_>) -> Result<Self, ValueError> {
+        Ok(match value {
+            ValueRef::Bool(v) => Value::Bool(v),
+            ValueRef::I8(v) => Value::I8(v),
+            ValueRef::I16(v) => Value::I16(v),
+            ValueRef::I32(v) => Value::I32(v),
+            ValueRef::I64(v) => Value::I64(v),
+            ValueRef::I128(v) => Value::I128(v),
+            ValueRef::U8(v) => Value::U8(v),
+            ValueRef::U16(v) => Value::U16(v),
+            ValueRef::U32(v) => Value::U32(v),
+            ValueRef::U64(v) => Value::U64(v),
+            ValueRef::U128(v) => Value::U128(v),
+            ValueRef::F32(v) => Value::F32(v),
+            ValueRef::F64(v) => Value::F64(v),
+            ValueRef::Char(v) => Value::Char(v),
+            ValueRef::Str(v) => Value::String(v.to_string()),
+            ValueRef::Bytes(v) => Value::Bytes(v.to_vec()),
+            ValueRef::None => Value::None,
+            ValueRef::Some(value) => Value::Some(Box::new(value.to_value()?)),
+            ValueRef::Unit => Value::Unit,
+            ValueRef::UnitStruct { name } => Value::UnitStruct { name },
+            ValueRef::UnitVariant { name, variant_index, variant } => Value::UnitVariant { name, variant_index, variant },
+            ValueRef::NewtypeStruct { name, value } => Value::NewtypeStruct {
+                name,
+                value: Box::new(value.to_value()?),
+            },
+            ValueRef::NewtypeVariant { name, variant_index, variant, value } => Value::NewtypeVariant {
+                name,
+                variant_index,
+                variant,
+                value: Box::new(value.to_value()?),
+            },
+            ValueRef::Seq { len } => Value::Seq {
+                len,
+                elements: Vec::with_capacity(capacity(len.unwrap_or(0))),
+            },
+            ValueRef::Tuple { len } => Value::Tuple {
+                len,
+                elements: Vec::with_capacity(capacity(len)),
+            },
+            ValueRef::TupleStruct { name, len } => Value::TupleStruct {
+                name,
+                len,
+                fields: Vec::with_capacity(capacity(len)),
+            },
+            ValueRef::TupleVariant { name, variant_index, variant, len } => Value::TupleVariant {
+                name,
+                variant_index,
+                variant,
+                len,
+                fields: Vec::with_capacity(capacity(len)),
+            },
+            ValueRef::Map { len } => Value::Map {
+                len,
+                entries: Vec::with_capacity(capacity(len.unwrap_or(0))),
+            },
+            ValueRef::Struct { name, len } => Value::Struct {
+                name,
+                len,
+                fields: Vec::with_capacity(capacity(len)),
+                skipped: Vec::new(),
+            },
+            ValueRef::StructVariant { name, variant_index, variant, len } => Value::StructVariant {
+                name,
+                variant_index,
+                variant,
+                len,
+                fields: Vec::with_capacity(capacity(len)),
+                skipped: Vec::new(),
+            },
+        })
+    }
+}
+
+/// Preallocated capacity for a length hint, which can be anything a `Serialize` implementation passed.
+fn capacity(len: usize) -> usize {
+    len.min(4096)
+}
+
+/// Replays the value, calling the same serialize methods with the same names, variant indexes and lengths.
+///
+/// The replay isn't call-for-call for maps: entries are passed to `serialize_entry`, even when they were
+/// serialized by `serialize_key` and `serialize_value`.
+impl Serialize for Value {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use ser::{
+            SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple, SerializeTupleStruct,
+            SerializeTupleVariant,
+        };
+
+        match self {
+            Value::Bool(v) => serializer.serialize_bool(*v),
+            Value::I8(v) => serializer.serialize_i8(*v),
+            Value::I16(v) => serializer.serialize_i16(*v),
+            Value::I32(v) => serializer.serialize_i32(*v),
+            Value::I64(v) => serializer.serialize_i64(*v),
+            Value::I128(v) => serializer.serialize_i128(*v),
+            Value::U8(v) => serializer.serialize_u8(*v),
+            Value::U16(v) => serializer.serialize_u16(*v),
+            Value::U32(v) => serializer.serialize_u32(*v),
+            Value::U64(v) => serializer.serialize_u64(*v),
+            Value::U128(v) => serializer.serialize_u128(*v),
+            Value::F32(v) => serializer.serialize_f32(*v),
+            Value::F64(v) => serializer.serialize_f64(*v),
+            Value::Char(v) => serializer.serialize_char(*v),
+            Value::String(v) => serializer.serialize_str(v),
+            Value::Bytes(v) => serializer.serialize_bytes(v),
+            Value::None => serializer.serialize_none(),
+            Value::Some(value) => serializer.serialize_some(value),
+            Value::Unit => serializer.serialize_unit(),
+            Value::UnitStruct { name } => serializer.serialize_unit_struct(name),
+            Value::UnitVariant { name, variant_index, variant } => {
+                serializer.serialize_unit_variant(name, *variant_index, variant)
+            }
+            Value::NewtypeStruct { name, value } => serializer.serialize_newtype_struct(name, value),
+            Value::NewtypeVariant { name, variant_index, variant, value } => {
+                serializer.serialize_newtype_variant(name, *variant_index, variant, value)
+            }
+            Value::Seq { len, elements } => {
+                let mut seq = serializer.serialize_seq(*len)?;
+                for element in elements {
+                    seq.serialize_element(element)?;
+                }
+                seq.end()
+            }
+            Value::Tuple { len, elements } => {
+                let mut tuple = serializer.serialize_tuple(*len)?;
+                for element in elements {
+                    tuple.serialize_element(element)?;
+                }
+                tuple.end()
+            }
+            Value::TupleStruct { name, len, fields } => {
+                let mut tuple_struct = serializer.serialize_tuple_struct(name, *len)?;
+                for field in fields {
+                    tuple_struct.serialize_field(field)?;
+                }
+                tuple_struct.end()
+            }
+            Value::TupleVariant { name, variant_index, variant, len, fields } => {
+                let mut tuple_variant = serializer.serialize_tuple_variant(name, *variant_index, variant, *len)?;
+                for field in fields {
+                    tuple_variant.serialize_field(field)?;
+                }
+                tuple_variant.end()
+            }
+            Value::Map { len, entries } => {
+                let mut map = serializer.serialize_map(*len)?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Value::Struct { name, len, fields, skipped } => {
+                let mut r#struct = serializer.serialize_struct(name, *len)?;
+                for (key, value) in in_order(fields, skipped) {
+                    match value {
+                        Some(value) => r#struct.serialize_field(key, value)?,
+                        None => r#struct.skip_field(key)?,
+                    }
+                }
+                r#struct.end()
+            }
+            Value::StructVariant { name, variant_index, variant, len, fields, skipped } => {
+                let mut struct_variant = serializer.serialize_struct_variant(name, *variant_index, variant, *len)?;
+                for (key, value) in in_order(fields, skipped) {
+                    match value {
+                        Some(value) => struct_variant.serialize_field(key, value)?,
+                        None => struct_variant.skip_field(key)?,
+                    }
+                }
+                struct_variant.end()
+            }
+        }
+    }
+}
+
+/// Fields and skipped fields in the order of the calls, skipped ones without value.
+fn in_order<'a>(
+    fields: &'a [(&'static str, Value)],
+    skipped: &'a [(usize, &'static str)],
+) -> impl Iterator<Item = (&'static str, Option<&'a Value>)> {
+    let mut skipped = skipped.iter().peekable();
+    let mut fields = fields.iter().enumerate().peekable();
+
+    core::iter::from_fn(move || match (skipped.peek(), fields.peek()) {
+        (Some((position, _)), Some((i, _))) if position > i => fields.next().map(|(_, (key, value))| (*key, Some(value))),
+        (Some(_), _) => skipped.next().map(|(_, key)| (*key, None)),
+        (None, _) => fields.next().map(|(_, (key, value))| (*key, Some(value))),
+    })
+}
+
+/// Serializes `value` into [Value].
+pub fn to_value<T: ?Sized + Serialize>(value: &T) -> Result<Value, ValueError> {
+    value.serialize(ValueSerializer)
+}
+
+/// Error of [ValueSerializer] and of deserialization from [Value], the message of a custom error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValueError(String);
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl ser::StdError for ValueError {}
+
+impl ser::Error for ValueError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ValueError(msg.to_string())
+    }
+}
+
+impl de::Error for ValueError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ValueError(msg.to_string())
+    }
+}
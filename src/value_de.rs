@@ -0,0 +1,433 @@
+use crate as impl_serialize;
+use crate::{Value, ValueError};
+use serde::de::{self, value::BorrowedStrDeserializer, Deserialize, Deserializer, IntoDeserializer};
+
+/// Deserializes `T` from borrowed [Value].
+///
+/// Owned [Value] is a `Deserializer` too, use `T::deserialize(value)` to move strings and bytes out of it.
+/// # Example
+#[doc = include_str!("../docs/value_de.md")]
+pub fn from_value<'de, T: Deserialize<'de>>(value: &'de Value) -> Result<T, ValueError> {
+    T::deserialize(value)
+}
+
+/// Self-describing deserializer over borrowed value.
+///
+/// Strings and bytes are borrowed from the value. Enum variants are visited by name with
+/// [`visit_enum`](de::Visitor::visit_enum), a string is accepted as unit variant.
+impl<'de> Deserializer<'de> for &'de Value {
+    type Error = ValueError;
+
+    impl_deserialize!(
+        match self {
+            Value::Bool(v) => visitor.visit_bool(*v),
+            Value::I8(v) => visitor.visit_i8(*v),
+            Value::I16(v) => visitor.visit_i16(*v),
+            Value::I32(v) => visitor.visit_i32(*v),
+            Value::I64(v) => visitor.visit_i64(*v),
+            Value::I128(v) => visitor.visit_i128(*v),
+            Value::U8(v) => visitor.visit_u8(*v),
+            Value::U16(v) => visitor.visit_u16(*v),
+            Value::U32(v) => visitor.visit_u32(*v),
+            Value::U64(v) => visitor.visit_u64(*v),
+            Value::U128(v) => visitor.visit_u128(*v),
+            Value::F32(v) => visitor.visit_f32(*v),
+            Value::F64(v) => visitor.visit_f64(*v),
+            Value::Char(v) => visitor.visit_char(*v),
+            Value::String(v) => visitor.visit_borrowed_str(v),
+            Value::Bytes(v) => visitor.visit_borrowed_bytes(v),
+            Value::None => visitor.visit_none(),
+            Value::Some(value) => visitor.visit_some(&**value),
+            Value::Unit | Value::UnitStruct { .. } => visitor.visit_unit(),
+            Value::NewtypeStruct { value, .. } => visitor.visit_newtype_struct(&**value),
+            Value::Seq { elements, .. } | Value::Tuple { elements, .. } | Value::TupleStruct { fields: elements, .. } => {
+                visit_seq(elements.iter(), visitor)
+            }
+            Value::Map { entries, .. } => visit_map(entries.iter().map(|(key, value)| (key, value)), visitor),
+            Value::Struct { fields, .. } => visit_map(fields.iter().map(|(key, value)| field(key, value)), visitor),
+            Value::UnitVariant { .. }
+            | Value::NewtypeVariant { .. }
+            | Value::TupleVariant { .. }
+            | Value::StructVariant { .. } => visitor.visit_enum(self),
+        },
+        any
+    );
+
+    impl_deserialize!(
+        match self {
+            Value::None => visitor.visit_none(),
+            Value::Some(value) => visitor.visit_some(&**value),
+            _ => visitor.visit_some(self),
+        },
+        option
+    );
+
+    impl_deserialize!(
+        match self {
+            Value::Unit | Value::UnitStruct { .. } => visitor.visit_unit(),
+            _ => self.deserialize_any(visitor),
+        },
+        [unit, unit_struct]
+    );
+
+    impl_deserialize!(
+        match self {
+            Value::NewtypeStruct { value, .. } => visitor.visit_newtype_struct(&**value),
+            _ => visitor.visit_newtype_struct(self),
+        },
+        newtype_struct
+    );
+
+    impl_deserialize!(
+        match self {
+            Value::UnitVariant { .. }
+            | Value::NewtypeVariant { .. }
+            | Value::TupleVariant { .. }
+            | Value::StructVariant { .. } => visitor.visit_enum(self),
+            Value::String(variant) => visitor.visit_enum(variant.as_str().into_deserializer()),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &"enum")),
+        },
+        enum
+    );
+
+    impl_deserialize!(visitor.visit_unit(), ignored_any);
+
+    impl_deserialize!(
+        self.deserialize_any(visitor),
+        [
+            bool,
+            i8, i16, i32, i64, i128,
+            u8, u16, u32, u64, u128,
+            f32, f64,
+            char, str, string,
+            bytes, byte_buf,
+            seq, tuple, tuple_struct,
+            map, struct,
+            identifier
+        ]
+    );
+}
+
+impl<'de> IntoDeserializer<'de, ValueError> for &'de Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &'de Value {
+    type Error = ValueError;
+    type Variant = Self;
+
+    fn variant_seed<S: de::DeserializeSeed<'de>>(self, seed: S) -> Result<(S::Value, Self), ValueError> {
+        let variant = match self {
+            Value::UnitVariant { variant, .. }
+            | Value::NewtypeVariant { variant, .. }
+            | Value::TupleVariant { variant, .. }
+            | Value::StructVariant { variant, .. } => *variant,
+            _ => return Err(de::Error::invalid_type(self.unexpected(), &"enum variant")),
+        };
+
+        seed.deserialize(BorrowedStrDeserializer::new(variant))
+            .map(|variant| (variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &'de Value {
+    type Error = ValueError;
+
+    fn unit_variant(self) -> Result<(), ValueError> {
+        match self {
+            Value::UnitVariant { .. } => Ok(()),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &"unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<S: de::DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value, ValueError> {
+        match self {
+            Value::NewtypeVariant { value, .. } => seed.deserialize(&**value),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &"newtype variant")),
+        }
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            Value::TupleVariant { fields, .. } => visit_seq(fields.iter(), visitor),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &"tuple variant")),
+        }
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        match self {
+            Value::StructVariant { fields, .. } => {
+                visit_map(fields.iter().map(|(key, value)| field(key, value)), visitor)
+            }
+            _ => Err(de::Error::invalid_type(self.unexpected(), &"struct variant")),
+        }
+    }
+}
+
+/// Self-describing deserializer over owned value.
+///
+/// Strings and bytes are moved out of the value, otherwise it is deserialized
+/// like borrowed [Value].
+impl<'de> Deserializer<'de> for Value {
+    type Error = ValueError;
+
+    impl_deserialize!(
+        match self {
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::I8(v) => visitor.visit_i8(v),
+            Value::I16(v) => visitor.visit_i16(v),
+            Value::I32(v) => visitor.visit_i32(v),
+            Value::I64(v) => visitor.visit_i64(v),
+            Value::I128(v) => visitor.visit_i128(v),
+            Value::U8(v) => visitor.visit_u8(v),
+            Value::U16(v) => visitor.visit_u16(v),
+            Value::U32(v) => visitor.visit_u32(v),
+            Value::U64(v) => visitor.visit_u64(v),
+            Value::U128(v) => visitor.visit_u128(v),
+            Value::F32(v) => visitor.visit_f32(v),
+            Value::F64(v) => visitor.visit_f64(v),
+            Value::Char(v) => visitor.visit_char(v),
+            Value::String(v) => visitor.visit_string(v),
+            Value::Bytes(v) => visitor.visit_byte_buf(v),
+            Value::None => visitor.visit_none(),
+            Value::Some(value) => visitor.visit_some(*value),
+            Value::Unit | Value::UnitStruct { .. } => visitor.visit_unit(),
+            Value::NewtypeStruct { value, .. } => visitor.visit_newtype_struct(*value),
+            Value::Seq { elements, .. } | Value::Tuple { elements, .. } | Value::TupleStruct { fields: elements, .. } => {
+                visit_seq(elements.into_iter(), visitor)
+            }
+            Value::Map { entries, .. } => visit_map(entries.into_iter(), visitor),
+            Value::Struct { fields, .. } => visit_map(fields.into_iter().map(|(key, value)| field(key, value)), visitor),
+            Value::UnitVariant { .. }
+            | Value::NewtypeVariant { .. }
+            | Value::TupleVariant { .. }
+            | Value::StructVariant { .. } => visitor.visit_enum(self),
+        },
+        any
+    );
+
+    impl_deserialize!(
+        match self {
+            Value::None => visitor.visit_none(),
+            Value::Some(value) => visitor.visit_some(*value),
+            _ => visitor.visit_some(self),
+        },
+        option
+    );
+
+    impl_deserialize!(
+        match self {
+            Value::Unit | Value::UnitStruct { .. } => visitor.visit_unit(),
+            _ => self.deserialize_any(visitor),
+        },
+        [unit, unit_struct]
+    );
+
+    impl_deserialize!(
+        match self {
+            Value::NewtypeStruct { value, .. } => visitor.visit_newtype_struct(*value),
+            _ => visitor.visit_newtype_struct(self),
+        },
+        newtype_struct
+    );
+
+    impl_deserialize!(
+        match self {
+            Value::UnitVariant { .. }
+            | Value::NewtypeVariant { .. }
+            | Value::TupleVariant { .. }
+            | Value::StructVariant { .. } => visitor.visit_enum(self),
+            Value::String(variant) => visitor.visit_enum(variant.into_deserializer()),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &"enum")),
+        },
+        enum
+    );
+
+    impl_deserialize!(visitor.visit_unit(), ignored_any);
+
+    impl_deserialize!(
+        self.deserialize_any(visitor),
+        [
+            bool,
+            i8, i16, i32, i64, i128,
+            u8, u16, u32, u64, u128,
+            f32, f64,
+            char, str, string,
+            bytes, byte_buf,
+            seq, tuple, tuple_struct,
+            map, struct,
+            identifier
+        ]
+    );
+}
+
+impl<'de> IntoDeserializer<'de, ValueError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for Value {
+    type Error = ValueError;
+    type Variant = Self;
+
+    fn variant_seed<S: de::DeserializeSeed<'de>>(self, seed: S) -> Result<(S::Value, Self), ValueError> {
+        let variant = match self {
+            Value::UnitVariant { variant, .. }
+            | Value::NewtypeVariant { variant, .. }
+            | Value::TupleVariant { variant, .. }
+            | Value::StructVariant { variant, .. } => variant,
+            _ => return Err(de::Error::invalid_type(self.unexpected(), &"enum variant")),
+        };
+
+        seed.deserialize(BorrowedStrDeserializer::new(variant))
+            .map(|variant| (variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Value {
+    type Error = ValueError;
+
+    fn unit_variant(self) -> Result<(), ValueError> {
+        match self {
+            Value::UnitVariant { .. } => Ok(()),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &"unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<S: de::DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value, ValueError> {
+        match self {
+            Value::NewtypeVariant { value, .. } => seed.deserialize(*value),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &"newtype variant")),
+        }
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            Value::TupleVariant { fields, .. } => visit_seq(fields.into_iter(), visitor),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &"tuple variant")),
+        }
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        match self {
+            Value::StructVariant { fields, .. } => {
+                visit_map(fields.into_iter().map(|(key, value)| field(key, value)), visitor)
+            }
+            _ => Err(de::Error::invalid_type(self.unexpected(), &"struct variant")),
+        }
+    }
+}
+
+fn visit_seq<'de, I, V>(elements: I, visitor: V) -> Result<V::Value, ValueError>
+where
+    I: ExactSizeIterator,
+    I::Item: Deserializer<'de, Error = ValueError>,
+    V: de::Visitor<'de>,
+{
+    let len = elements.len();
+    let mut seq = Elements(elements);
+    let value = visitor.visit_seq(&mut seq)?;
+
+    match seq.0.len() {
+        0 => Ok(value),
+        remaining => Err(de::Error::invalid_length(len, &ExpectedLen(len - remaining))),
+    }
+}
+
+fn visit_map<'de, I, K, V, T>(entries: I, visitor: T) -> Result<T::Value, ValueError>
+where
+    I: ExactSizeIterator<Item = (K, V)>,
+    K: Deserializer<'de, Error = ValueError>,
+    V: Deserializer<'de, Error = ValueError>,
+    T: de::Visitor<'de>,
+{
+    let len = entries.len();
+    let mut map = Entries { iter: entries, value: None };
+    let value = visitor.visit_map(&mut map)?;
+
+    match map.iter.len() {
+        0 => Ok(value),
+        remaining => Err(de::Error::invalid_length(len, &ExpectedLen(len - remaining))),
+    }
+}
+
+/// Field names of a struct as keys of a map.
+fn field<V>(key: &'static str, value: V) -> (BorrowedStrDeserializer<'static, ValueError>, V) {
+    (BorrowedStrDeserializer::new(key), value)
+}
+
+struct ExpectedLen(usize);
+
+impl de::Expected for ExpectedLen {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{} elements", self.0)
+    }
+}
+
+struct Elements<I>(I);
+
+impl<'de, I> de::SeqAccess<'de> for Elements<I>
+where
+    I: ExactSizeIterator,
+    I::Item: Deserializer<'de, Error = ValueError>,
+{
+    type Error = ValueError;
+
+    fn next_element_seed<S: de::DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>, ValueError> {
+        self.0.next().map(|value| seed.deserialize(value)).transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct Entries<I, V> {
+    iter: I,
+    value: Option<V>,
+}
+
+impl<'de, I, K, V> de::MapAccess<'de> for Entries<I, V>
+where
+    I: ExactSizeIterator<Item = (K, V)>,
+    K: Deserializer<'de, Error = ValueError>,
+    V: Deserializer<'de, Error = ValueError>,
+{
+    type Error = ValueError;
+
+    fn next_key_seed<S: de::DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>, ValueError> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<S: de::DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, ValueError> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::custom("next_value was called before next_key")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
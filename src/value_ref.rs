@@ -0,0 +1,214 @@
+use crate::erased::{ErasedError, Erasing, Slot};
+use crate::ValueKind;
+#[cfg(feature = "alloc")]
+use crate::{Value, ValueError};
+use core::fmt;
+use serde::ser::{Serialize, Serializer};
+
+/// Borrowed arguments of a serialize method, one variant per method of
+/// [serde::Serializer](https://docs.rs/serde/latest/serde/trait.Serializer.html).
+///
+/// Values of `some`, `newtype_struct` and `newtype_variant` are passed as [Erased].
+#[derive(Clone, Copy, Debug)]
+pub enum ValueRef<'a> {
+    /// `serialize_bool`
+    Bool(bool),
+    /// `serialize_i8`
+    I8(i8),
+    /// `serialize_i16`
+    I16(i16),
+    /// `serialize_i32`
+    I32(i32),
+    /// `serialize_i64`
+    I64(i64),
+    /// `serialize_i128`
+    I128(i128),
+    /// `serialize_u8`
+    U8(u8),
+    /// `serialize_u16`
+    U16(u16),
+    /// `serialize_u32`
+    U32(u32),
+    /// `serialize_u64`
+    U64(u64),
+    /// `serialize_u128`
+    U128(u128),
+    /// `serialize_f32`
+    F32(f32),
+    /// `serialize_f64`
+    F64(f64),
+    /// `serialize_char`
+    Char(char),
+    /// `serialize_str`
+    Str(&'a str),
+    /// `serialize_bytes`
+    Bytes(&'a [u8]),
+    /// `serialize_none`
+    None,
+    /// `serialize_some`
+    Some(Erased<'a>),
+    /// `serialize_unit`
+    Unit,
+    /// `serialize_unit_struct`
+    UnitStruct {
+        name: &'static str,
+    },
+    /// `serialize_unit_variant`
+    UnitVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    },
+    /// `serialize_newtype_struct`
+    NewtypeStruct {
+        name: &'static str,
+        value: Erased<'a>,
+    },
+    /// `serialize_newtype_variant`
+    NewtypeVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: Erased<'a>,
+    },
+    /// `serialize_seq`
+    Seq {
+        len: Option<usize>,
+    },
+    /// `serialize_tuple`
+    Tuple {
+        len: usize,
+    },
+    /// `serialize_tuple_struct`
+    TupleStruct {
+        name: &'static str,
+        len: usize,
+    },
+    /// `serialize_tuple_variant`
+    TupleVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    },
+    /// `serialize_map`
+    Map {
+        len: Option<usize>,
+    },
+    /// `serialize_struct`
+    Struct {
+        name: &'static str,
+        len: usize,
+    },
+    /// `serialize_struct_variant`
+    StructVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    },
+}
+
+impl<'a> ValueRef<'a> {
+    /// Returns kind of the method what received the arguments.
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            ValueRef::Bool(_) => ValueKind::Bool,
+            ValueRef::I8(_) => ValueKind::I8,
+            ValueRef::I16(_) => ValueKind::I16,
+            ValueRef::I32(_) => ValueKind::I32,
+            ValueRef::I64(_) => ValueKind::I64,
+            ValueRef::I128(_) => ValueKind::I128,
+            ValueRef::U8(_) => ValueKind::U8,
+            ValueRef::U16(_) => ValueKind::U16,
+            ValueRef::U32(_) => ValueKind::U32,
+            ValueRef::U64(_) => ValueKind::U64,
+            ValueRef::U128(_) => ValueKind::U128,
+            ValueRef::F32(_) => ValueKind::F32,
+            ValueRef::F64(_) => ValueKind::F64,
+            ValueRef::Char(_) => ValueKind::Char,
+            ValueRef::Str(_) => ValueKind::Str,
+            ValueRef::Bytes(_) => ValueKind::Bytes,
+            ValueRef::None => ValueKind::None,
+            ValueRef::Some(_) => ValueKind::Some,
+            ValueRef::Unit => ValueKind::Unit,
+            ValueRef::UnitStruct { .. } => ValueKind::UnitStruct,
+            ValueRef::UnitVariant { .. } => ValueKind::UnitVariant,
+            ValueRef::NewtypeStruct { .. } => ValueKind::NewtypeStruct,
+            ValueRef::NewtypeVariant { .. } => ValueKind::NewtypeVariant,
+            ValueRef::Seq { .. } => ValueKind::Seq,
+            ValueRef::Tuple { .. } => ValueKind::Tuple,
+            ValueRef::TupleStruct { .. } => ValueKind::TupleStruct,
+            ValueRef::TupleVariant { .. } => ValueKind::TupleVariant,
+            ValueRef::Map { .. } => ValueKind::Map,
+            ValueRef::Struct { .. } => ValueKind::Struct,
+            ValueRef::StructVariant { .. } => ValueKind::StructVariant,
+        }
+    }
+}
+
+/// Type-erased value passed to `serialize_some`, `serialize_newtype_struct` and `serialize_newtype_variant`.
+#[derive(Clone, Copy)]
+pub struct Erased<'a> {
+    value: &'a dyn ErasedSerialize,
+}
+
+impl<'a> Erased<'a> {
+    /// Erases type of `value`.
+    ///
+    /// Takes reference to reference, so unsized values like `str` can be erased too.
+    pub fn new<T: ?Sized + Serialize>(value: &'a &'a T) -> Self {
+        Erased { value }
+    }
+
+    /// Returns kind of the first serialize method called by the value, see [`ValueKind::of`].
+    pub fn kind(&self) -> Option<ValueKind> {
+        self.value.kind()
+    }
+
+    /// Serializes the value into [Value].
+    #[cfg(feature = "alloc")]
+    pub fn to_value(&self) -> Result<Value, ValueError> {
+        self.value.to_value()
+    }
+}
+
+/// Serializes the value itself, without allocation.
+impl Serialize for Erased<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut slot = Slot::Serializer(serializer);
+        let result = self.value.serialize(Erasing(&mut slot));
+
+        slot.finish(result)
+    }
+}
+
+impl fmt::Debug for Erased<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Erased").field(&self.kind()).finish()
+    }
+}
+
+trait ErasedSerialize {
+    fn kind(&self) -> Option<ValueKind>;
+
+    fn serialize(&self, serializer: Erasing<'_>) -> Result<(), ErasedError>;
+
+    #[cfg(feature = "alloc")]
+    fn to_value(&self) -> Result<Value, ValueError>;
+}
+
+impl<T: ?Sized + Serialize> ErasedSerialize for &T {
+    fn kind(&self) -> Option<ValueKind> {
+        ValueKind::of(*self)
+    }
+
+    fn serialize(&self, serializer: Erasing<'_>) -> Result<(), ErasedError> {
+        Serialize::serialize(*self, serializer)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn to_value(&self) -> Result<Value, ValueError> {
+        crate::to_value(*self)
+    }
+}
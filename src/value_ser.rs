@@ -0,0 +1,206 @@
+use crate as impl_serialize;
+use crate::{Value, ValueError};
+use alloc::vec::Vec;
+use serde::ser::{self, Serialize};
+
+/// Serializer into owned [Value].
+/// # Example
+#[doc = include_str!("../docs/value.md")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = ValueError;
+
+    type SerializeMap = ValueCompound;
+    type SerializeSeq = ValueCompound;
+    type SerializeStruct = ValueCompound;
+    type SerializeStructVariant = ValueCompound;
+    type SerializeTuple = ValueCompound;
+    type SerializeTupleStruct = ValueCompound;
+    type SerializeTupleVariant = ValueCompound;
+
+    impl_serialize!(
+        value: Value::try_from(value_ref),
+        compound: Value::try_from(value_ref).map(ValueCompound::new),
+        all
+    );
+}
+
+/// Compound value under construction, returned by `serialize_seq`, `serialize_map`, etc. of [ValueSerializer].
+#[derive(Debug)]
+pub struct ValueCompound {
+    value: Value,
+    key: Option<Value>,
+}
+
+impl ValueCompound {
+    fn new(value: Value) -> Self {
+        ValueCompound { value, key: None }
+    }
+
+    fn elements(&mut self) -> &mut Vec<Value> {
+        match &mut self.value {
+            Value::Seq { elements, .. }
+            | Value::Tuple { elements, .. }
+            | Value::TupleStruct { fields: elements, .. }
+            | Value::TupleVariant { fields: elements, .. } => elements,
+            value => unreachable!("{} has no elements", value.kind()),
+        }
+    }
+
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        let value = value.serialize(ValueSerializer)?;
+
+        self.elements().push(value);
+        Ok(())
+    }
+
+    fn push_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), ValueError> {
+        let value = value.serialize(ValueSerializer)?;
+
+        match &mut self.value {
+            Value::Struct { fields, .. } | Value::StructVariant { fields, .. } => fields.push((key, value)),
+            value => unreachable!("{} has no fields", value.kind()),
+        }
+        Ok(())
+    }
+
+    fn skip_field(&mut self, key: &'static str) {
+        match &mut self.value {
+            Value::Struct { fields, skipped, .. } | Value::StructVariant { fields, skipped, .. } => {
+                skipped.push((fields.len(), key))
+            }
+            value => unreachable!("{} has no fields", value.kind()),
+        }
+    }
+
+    fn push_entry(&mut self, key: Value, value: Value) {
+        match &mut self.value {
+            Value::Map { entries, .. } => entries.push((key, value)),
+            value => unreachable!("{} has no entries", value.kind()),
+        }
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        match self.key {
+            Some(_) => Err(ser::Error::custom("serialize_value was not called after serialize_key")),
+            None => Ok(self.value),
+        }
+    }
+}
+
+impl ser::SerializeSeq for ValueCompound {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        ValueCompound::end(self)
+    }
+}
+
+impl ser::SerializeTuple for ValueCompound {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        ValueCompound::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for ValueCompound {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        ValueCompound::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for ValueCompound {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        ValueCompound::end(self)
+    }
+}
+
+impl ser::SerializeMap for ValueCompound {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), ValueError> {
+        self.key = Some(key.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ValueError> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| ser::Error::custom("serialize_value was called before serialize_key"))?;
+
+        let value = value.serialize(ValueSerializer)?;
+
+        self.push_entry(key, value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        ValueCompound::end(self)
+    }
+}
+
+impl ser::SerializeStruct for ValueCompound {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), ValueError> {
+        self.push_field(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), ValueError> {
+        ValueCompound::skip_field(self, key);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        ValueCompound::end(self)
+    }
+}
+
+impl ser::SerializeStructVariant for ValueCompound {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), ValueError> {
+        self.push_field(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), ValueError> {
+        ValueCompound::skip_field(self, key);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        ValueCompound::end(self)
+    }
+}
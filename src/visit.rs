@@ -0,0 +1,313 @@
+/// Macro for fast and easy implementing visit methods in
+/// [serde::de::Visitor](https://docs.rs/serde/latest/serde/de/trait.Visitor.html) trait.
+///
+/// The `impl` block must name its lifetime `'de`, because borrowed and nested arms refer to it.
+/// Methods accepting plain values are generic over the error type `E: serde::de::Error`,
+/// the others return the error of their `deserializer`, `seq`, `map` or `data` argument.
+/// # Example
+#[doc = include_str!("../docs/visit.md")]
+#[macro_export]
+macro_rules! impl_visit {
+    ($get_result:expr, [$($type:ident),+]) => {
+        $(
+            $crate::impl_visit!($get_result, $type);
+        )+
+    };
+
+    ($get_result:expr, bool) => {
+        impl_serialize::unhygienic! {
+            fn visit_bool<E: serde::de::Error>(self, v: bool) -> core::result::Result<Self::Value, E> {
+                let value_type = "bool";
+                let unexpected = serde::de::Unexpected::Bool(v);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, i8) => {
+        impl_serialize::unhygienic! {
+            fn visit_i8<E: serde::de::Error>(self, v: i8) -> core::result::Result<Self::Value, E> {
+                let value_type = "i8";
+                let unexpected = serde::de::Unexpected::Signed(v as i64);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, i16) => {
+        impl_serialize::unhygienic! {
+            fn visit_i16<E: serde::de::Error>(self, v: i16) -> core::result::Result<Self::Value, E> {
+                let value_type = "i16";
+                let unexpected = serde::de::Unexpected::Signed(v as i64);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, i32) => {
+        impl_serialize::unhygienic! {
+            fn visit_i32<E: serde::de::Error>(self, v: i32) -> core::result::Result<Self::Value, E> {
+                let value_type = "i32";
+                let unexpected = serde::de::Unexpected::Signed(v as i64);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, i64) => {
+        impl_serialize::unhygienic! {
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> core::result::Result<Self::Value, E> {
+                let value_type = "i64";
+                let unexpected = serde::de::Unexpected::Signed(v);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, i128) => {
+        impl_serialize::unhygienic! {
+            fn visit_i128<E: serde::de::Error>(self, v: i128) -> core::result::Result<Self::Value, E> {
+                let value_type = "i128";
+                let unexpected = serde::de::Unexpected::Other("i128");
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, u8) => {
+        impl_serialize::unhygienic! {
+            fn visit_u8<E: serde::de::Error>(self, v: u8) -> core::result::Result<Self::Value, E> {
+                let value_type = "u8";
+                let unexpected = serde::de::Unexpected::Unsigned(v as u64);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, u16) => {
+        impl_serialize::unhygienic! {
+            fn visit_u16<E: serde::de::Error>(self, v: u16) -> core::result::Result<Self::Value, E> {
+                let value_type = "u16";
+                let unexpected = serde::de::Unexpected::Unsigned(v as u64);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, u32) => {
+        impl_serialize::unhygienic! {
+            fn visit_u32<E: serde::de::Error>(self, v: u32) -> core::result::Result<Self::Value, E> {
+                let value_type = "u32";
+                let unexpected = serde::de::Unexpected::Unsigned(v as u64);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, u64) => {
+        impl_serialize::unhygienic! {
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> core::result::Result<Self::Value, E> {
+                let value_type = "u64";
+                let unexpected = serde::de::Unexpected::Unsigned(v);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, u128) => {
+        impl_serialize::unhygienic! {
+            fn visit_u128<E: serde::de::Error>(self, v: u128) -> core::result::Result<Self::Value, E> {
+                let value_type = "u128";
+                let unexpected = serde::de::Unexpected::Other("u128");
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, f32) => {
+        impl_serialize::unhygienic! {
+            fn visit_f32<E: serde::de::Error>(self, v: f32) -> core::result::Result<Self::Value, E> {
+                let value_type = "f32";
+                let unexpected = serde::de::Unexpected::Float(v as f64);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, f64) => {
+        impl_serialize::unhygienic! {
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> core::result::Result<Self::Value, E> {
+                let value_type = "f64";
+                let unexpected = serde::de::Unexpected::Float(v);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, char) => {
+        impl_serialize::unhygienic! {
+            fn visit_char<E: serde::de::Error>(self, v: char) -> core::result::Result<Self::Value, E> {
+                let value_type = "char";
+                let unexpected = serde::de::Unexpected::Char(v);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, str) => {
+        impl_serialize::unhygienic! {
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> core::result::Result<Self::Value, E> {
+                let value_type = "str";
+                let unexpected = serde::de::Unexpected::Str(v);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, borrowed_str) => {
+        impl_serialize::unhygienic! {
+            fn visit_borrowed_str<E: serde::de::Error>(self, v: &'de str) -> core::result::Result<Self::Value, E> {
+                let value_type = "borrowed_str";
+                let unexpected = serde::de::Unexpected::Str(v);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, string) => {
+        impl_serialize::unhygienic! {
+            fn visit_string<E: serde::de::Error>(self, v: impl_serialize::__private::String) -> core::result::Result<Self::Value, E> {
+                let value_type = "string";
+                let unexpected = serde::de::Unexpected::Str(&v);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, bytes) => {
+        impl_serialize::unhygienic! {
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> core::result::Result<Self::Value, E> {
+                let value_type = "bytes";
+                let unexpected = serde::de::Unexpected::Bytes(v);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, borrowed_bytes) => {
+        impl_serialize::unhygienic! {
+            fn visit_borrowed_bytes<E: serde::de::Error>(self, v: &'de [u8]) -> core::result::Result<Self::Value, E> {
+                let value_type = "borrowed_bytes";
+                let unexpected = serde::de::Unexpected::Bytes(v);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, byte_buf) => {
+        impl_serialize::unhygienic! {
+            fn visit_byte_buf<E: serde::de::Error>(self, v: impl_serialize::__private::Vec<u8>) -> core::result::Result<Self::Value, E> {
+                let value_type = "byte_buf";
+                let unexpected = serde::de::Unexpected::Bytes(&v);
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, none) => {
+        impl_serialize::unhygienic! {
+            fn visit_none<E: serde::de::Error>(self) -> core::result::Result<Self::Value, E> {
+                let value_type = "none";
+                let unexpected = serde::de::Unexpected::Option;
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, unit) => {
+        impl_serialize::unhygienic! {
+            fn visit_unit<E: serde::de::Error>(self) -> core::result::Result<Self::Value, E> {
+                let value_type = "unit";
+                let unexpected = serde::de::Unexpected::Unit;
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, some) => {
+        impl_serialize::unhygienic! {
+            fn visit_some<D: serde::de::Deserializer<'de>>(self, deserializer: D) -> core::result::Result<Self::Value, D::Error> {
+                let value_type = "some";
+                let unexpected = serde::de::Unexpected::Option;
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, newtype_struct) => {
+        impl_serialize::unhygienic! {
+            fn visit_newtype_struct<D: serde::de::Deserializer<'de>>(self, deserializer: D) -> core::result::Result<Self::Value, D::Error> {
+                let value_type = "newtype_struct";
+                let unexpected = serde::de::Unexpected::NewtypeStruct;
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, seq) => {
+        impl_serialize::unhygienic! {
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(self, seq: A) -> core::result::Result<Self::Value, A::Error> {
+                let value_type = "seq";
+                let unexpected = serde::de::Unexpected::Seq;
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, map) => {
+        impl_serialize::unhygienic! {
+            fn visit_map<A: serde::de::MapAccess<'de>>(self, map: A) -> core::result::Result<Self::Value, A::Error> {
+                let value_type = "map";
+                let unexpected = serde::de::Unexpected::Map;
+
+                $get_result
+            }
+        }
+    };
+
+    ($get_result:expr, enum) => {
+        impl_serialize::unhygienic! {
+            fn visit_enum<A: serde::de::EnumAccess<'de>>(self, data: A) -> core::result::Result<Self::Value, A::Error> {
+                let value_type = "enum";
+                let unexpected = serde::de::Unexpected::Enum;
+
+                $get_result
+            }
+        }
+    };
+}
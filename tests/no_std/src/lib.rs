@@ -0,0 +1,164 @@
+//! Build with `cargo build --manifest-path tests/no_std/Cargo.toml`, and with `--features alloc`
+//! for the arms what take owned values.
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::fmt;
+use impl_serialize::{impl_deserialize, impl_serialize, impl_visit, Rejecting, ValueRef};
+use serde::{de, ser, Serialize};
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    CannotSerializeFrom(&'static str),
+    Custom,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::CannotSerializeFrom(value_type) => write!(f, "Cannot serialize value from {}", value_type),
+            Error::Custom => f.write_str("Custom"),
+        }
+    }
+}
+
+impl ser::StdError for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        Error::Custom
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        Error::Custom
+    }
+}
+
+pub struct U8Serializer;
+
+impl ser::Serializer for U8Serializer {
+    type Ok = u8;
+    type Error = Error;
+
+    type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeSeq = Rejecting<Self::Ok, Self::Error>;
+    type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+    impl_serialize!(Ok(v), u8);
+
+    impl_serialize!(Ok(Rejecting::with_error(value_type, Error::CannotSerializeFrom)), seq);
+
+    impl_serialize!(Err(Error::CannotSerializeFrom(value_type)), [
+        bool,
+        bytes,
+        i8, i16, i32, i64,
+        u16, u32, u64,
+        f32, f64,
+        char,
+        str,
+        none, some, unit,
+        unit_struct, unit_variant,
+        newtype_struct, newtype_variant,
+        map,
+        tuple, tuple_struct, tuple_variant,
+        struct, struct_variant
+    ]);
+
+    //serde requires it without alloc
+    fn collect_str<T: ?Sized + fmt::Display>(self, _value: &T) -> Result<Self::Ok, Self::Error> {
+        Err(Error::CannotSerializeFrom("str"))
+    }
+}
+
+/// Inner value of `some`, `newtype_struct` and `newtype_variant` serialized by [U8Serializer].
+pub fn inner_u8(value: ValueRef<'_>) -> Option<Result<u8, Error>> {
+    match value {
+        ValueRef::Some(value) | ValueRef::NewtypeStruct { value, .. } | ValueRef::NewtypeVariant { value, .. } => {
+            Some(value.serialize(U8Serializer))
+        }
+        _ => None,
+    }
+}
+
+pub struct U8Deserializer(pub u8);
+
+impl<'de> de::Deserializer<'de> for U8Deserializer {
+    type Error = Error;
+
+    impl_deserialize!(visitor.visit_u8(self.0), [any, u8]);
+
+    impl_deserialize!(Err(de::Error::invalid_type(de::Unexpected::Unsigned(self.0 as u64), &visitor)), [
+        bool,
+        i8, i16, i32, i64, i128,
+        u16, u32, u64, u128,
+        f32, f64,
+        char, str, string,
+        bytes, byte_buf,
+        option, unit,
+        unit_struct, newtype_struct,
+        seq, tuple, tuple_struct,
+        map, struct, enum,
+        identifier, ignored_any
+    ]);
+}
+
+pub struct U8Visitor;
+
+impl<'de> de::Visitor<'de> for U8Visitor {
+    type Value = u8;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("u8")
+    }
+
+    impl_visit!(Ok(v), u8);
+
+    impl_visit!(Err(de::Error::invalid_type(unexpected, &self)), [
+        bool,
+        i8, i16, i32, i64, i128,
+        u16, u32, u64, u128,
+        f32, f64,
+        char,
+        str, borrowed_str,
+        bytes, borrowed_bytes,
+        none, some, unit,
+        newtype_struct,
+        seq, map, enum
+    ]);
+}
+
+//String and Vec are not in scope here, impl_visit! names them by itself
+#[cfg(feature = "alloc")]
+pub struct LenVisitor;
+
+#[cfg(feature = "alloc")]
+impl<'de> de::Visitor<'de> for LenVisitor {
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("owned string or bytes")
+    }
+
+    impl_visit!(Ok(v.len()), [string, byte_buf]);
+
+    impl_visit!(Err(de::Error::invalid_type(unexpected, &self)), [
+        bool,
+        i8, i16, i32, i64, i128,
+        u8, u16, u32, u64, u128,
+        f32, f64,
+        char,
+        str, borrowed_str,
+        bytes, borrowed_bytes,
+        none, some, unit,
+        newtype_struct,
+        seq, map, enum
+    ]);
+}
@@ -0,0 +1,148 @@
+//! Exported macros invoked by path, without importing them.
+
+use impl_serialize::{Registry, Stats, ValueKind};
+use serde::ser::{self, Serializer};
+use std::cell::Cell;
+
+struct BoolSerializer;
+
+impl ser::Serializer for BoolSerializer {
+    type Ok = ();
+    type Error = serde::de::value::Error;
+
+    type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+    impl_serialize::impl_serialize!(Ok(()), bool);
+
+    impl_serialize::impl_serialize!(Err(ser::Error::custom(value_type)), [
+        bytes,
+        i8, i16, i32, i64, i128,
+        u8, u16, u32, u64, u128,
+        f32, f64,
+        char,
+        str,
+        none, some, unit,
+        unit_struct, unit_variant,
+        newtype_struct, newtype_variant,
+        seq, map,
+        tuple, tuple_struct, tuple_variant,
+        struct, struct_variant
+    ]);
+}
+
+impl_serialize::test_serializer!(BoolSerializer, accept [bool], reject [_]);
+
+impl_serialize::test_serializer!(bool_serializer_rejects_text: BoolSerializer, accept [], reject [char, str, bytes]);
+
+struct Counting<'a> {
+    stats: &'a Stats,
+    calls: &'a Cell<usize>,
+}
+
+impl_serialize::impl_serialize! {
+    impl<'a> ser::Serializer for Counting<'a> {
+        type Ok = String;
+        type Error = serde::de::value::Error;
+
+        type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+        impl_serialize!(
+            #[inline]
+            counted(self.stats),
+            before = { self.calls.set(self.calls.get() + 1) },
+            validate range(..=10) => Ok(v.to_string()),
+            integers
+        );
+
+        impl_serialize!(non_finite = saturate, Ok(v.to_string()), floats);
+
+        impl_serialize!(Err(ser::Error::custom(value_type)), [
+            bool,
+            char,
+            str,
+            bytes,
+            none, some, unit,
+            unit_struct, unit_variant,
+            newtype_struct, newtype_variant,
+            seq, map,
+            tuple, tuple_struct, tuple_variant,
+            struct, struct_variant
+        ]);
+    }
+}
+
+#[test]
+fn block_form() {
+    let (stats, calls) = (Stats::new(), Cell::new(0));
+    let serializer = || Counting { stats: &stats, calls: &calls };
+
+    assert_eq!(serializer().serialize_u8(7).unwrap(), "7");
+    assert!(serializer().serialize_i64(11).is_err());
+    assert_eq!(serializer().serialize_f64(f64::NAN).unwrap(), "0");
+    assert!(serializer().serialize_bool(true).is_err());
+
+    assert_eq!(calls.get(), 2);
+    assert_eq!((stats.get(ValueKind::U8), stats.get(ValueKind::I64)), (1, 1));
+    assert_eq!(Counting::IMPLEMENTED_BY_MACRO.len(), 3);
+}
+
+struct RejectingDeserializer;
+
+impl<'de> serde::de::Deserializer<'de> for RejectingDeserializer {
+    type Error = serde::de::value::Error;
+
+    impl_serialize::impl_deserialize!(Err(serde::de::Error::custom(value_type)), [
+        any,
+        bool,
+        i8, i16, i32, i64, i128,
+        u8, u16, u32, u64, u128,
+        f32, f64,
+        char, str, string,
+        bytes, byte_buf,
+        option, unit,
+        unit_struct, newtype_struct,
+        seq, tuple, tuple_struct,
+        map, struct, enum,
+        identifier, ignored_any
+    ]);
+}
+
+#[test]
+fn deserialize_list() {
+    use serde::de::{Deserializer, IgnoredAny};
+
+    let error = RejectingDeserializer.deserialize_u16(IgnoredAny).unwrap_err();
+    assert_eq!(error.to_string(), "u16");
+}
+
+struct IntVisitor;
+
+impl<'de> serde::de::Visitor<'de> for IntVisitor {
+    type Value = i64;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("an integer")
+    }
+
+    impl_serialize::impl_visit!(Ok(v as i64), [i8, i16, i32, i64, u8, u16, u32]);
+}
+
+#[test]
+fn visit_list() {
+    use serde::de::{value::U16Deserializer, Deserializer, IntoDeserializer};
+
+    let deserializer: U16Deserializer<serde::de::value::Error> = 7u16.into_deserializer();
+    assert_eq!(deserializer.deserialize_any(IntVisitor).unwrap(), 7);
+}